@@ -1,10 +1,12 @@
 import 'dotenv/config';
-import { serve } from '@hono/node-server';
+import { rmSync } from 'node:fs';
+import { createAdaptorServer, serve } from '@hono/node-server';
 import { Hono } from 'hono';
 import { cors } from 'hono/cors';
 import { serveStatic } from '@hono/node-server/serve-static';
 import { agentRoutes } from './routes/chat.js';
 import { previewRoutes } from './routes/preview.js';
+import { stopAllPreviews } from './preview/vite-server.js';
 import { fileRoutes } from './routes/files.js';
 import { mcpRoutes } from './routes/mcp.js';
 import { deviceRoutes } from './routes/devices.js';
@@ -15,10 +17,13 @@ import { knowledgeRoutes } from './routes/knowledge.js';
 import { robotRoutes } from './routes/robots.js';
 import { marketplaceRoutes } from './routes/marketplace.js';
 import { skillRoutes } from './routes/skills.js';
+import { API_PROTOCOL, API_VERSION } from './lib/version.js';
+import { shellTokenMiddleware } from './middleware/shell-token.js';
 
 const app = new Hono();
 
 app.use('/*', cors());
+app.use('/*', shellTokenMiddleware(process.env.OPENASST_API_TOKEN));
 
 app.route('/agent', agentRoutes);
 app.route('/preview', previewRoutes);
@@ -40,7 +45,13 @@ app.get('/health', async (c) => {
     const p = await findClaudeCodePath();
     claudeCode = !!p;
   } catch {}
-  return c.json({ status: 'ok', claudeCode });
+  return c.json({
+    status: 'ok',
+    service: 'openasst-api',
+    version: API_VERSION,
+    protocol: API_PROTOCOL,
+    claudeCode,
+  });
 });
 
 // Production: serve frontend static files
@@ -50,10 +61,33 @@ if (process.env.NODE_ENV === 'production') {
   app.get('*', serveStatic({ path: './packages/frontend/dist/index.html' }));
 }
 
-const port = Number(process.env.PORT) || (process.env.NODE_ENV === 'production' ? 2620 : 2026);
+// The desktop shell passes a private Unix socket (or Windows named pipe) so the
+// API is not exposed on a loopback TCP port.
+const socketPath = process.env.OPENASST_API_SOCKET;
 
-console.log(`OpenAsst API server starting on port ${port}`);
+if (socketPath) {
+  if (process.platform !== 'win32') rmSync(socketPath, { force: true });
+  const server = createAdaptorServer({ fetch: app.fetch });
+  server.listen(socketPath, () => {
+    console.log(`OpenAsst API server listening on ${socketPath}`);
+  });
+} else {
+  const port = Number(process.env.PORT) || (process.env.NODE_ENV === 'production' ? 2620 : 2026);
+  // Loopback only unless asked otherwise, as the Docker image does
+  const hostname = process.env.HOST || '127.0.0.1';
 
-serve({ fetch: app.fetch, port }, (info) => {
-  console.log(`OpenAsst API server running at http://127.0.0.1:${info.port}`);
-});
+  console.log(`OpenAsst API server starting on ${hostname}:${port}`);
+
+  serve({ fetch: app.fetch, port, hostname }, (info) => {
+    console.log(`OpenAsst API server running at http://${hostname}:${info.port}`);
+  });
+}
+
+// Stop preview servers with the API. The desktop app kills the API's whole
+// process group anyway; this covers running the API on its own.
+for (const signal of ['SIGINT', 'SIGTERM'] as const) {
+  process.on(signal, () => {
+    stopAllPreviews();
+    process.exit(0);
+  });
+}
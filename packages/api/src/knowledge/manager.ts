@@ -1,6 +1,6 @@
 import { readFileSync, readdirSync, writeFileSync, existsSync, mkdirSync } from 'fs';
 import { join, basename } from 'path';
-import { homedir } from 'os';
+import { workspacePath } from '../lib/data-dir.js';
 
 const GITHUB_API = 'https://api.github.com';
 
@@ -27,7 +27,7 @@ export interface KnowledgeIndex {
   files: string[];
 }
 
-const KNOWLEDGE_DIR = join(homedir(), 'openasst', 'knowledge');
+const KNOWLEDGE_DIR = workspacePath('knowledge');
 
 export class KnowledgeManager {
   private dir: string;
@@ -0,0 +1,27 @@
+import { homedir } from 'os';
+import { join } from 'path';
+
+// Directory holding all API state. The desktop shell points each profile at
+// its own directory through OPENASST_DATA_DIR; otherwise ~/.openasst.
+export const DATA_DIR = process.env.OPENASST_DATA_DIR || join(homedir(), '.openasst');
+
+// Log files written by the API, such as those of managed services. On Linux
+// the shell passes a directory under $XDG_STATE_HOME.
+export const LOG_DIR = process.env.OPENASST_LOG_DIR || join(DATA_DIR, 'logs');
+
+// Directory for downloads that can be thrown away, passed by the shell on
+// Linux as a directory under $XDG_CACHE_HOME. Unset, tools keep their own.
+export const CACHE_DIR = process.env.OPENASST_CACHE_DIR || undefined;
+
+// Files kept where users can see them, such as robot configurations and the
+// knowledge base: ~/openasst, or a directory of the profile given by the shell
+// through OPENASST_WORKSPACE_DIR.
+export const WORKSPACE_DIR = process.env.OPENASST_WORKSPACE_DIR || join(homedir(), 'openasst');
+
+export function dataPath(...segments: string[]): string {
+  return join(DATA_DIR, ...segments);
+}
+
+export function workspacePath(...segments: string[]): string {
+  return join(WORKSPACE_DIR, ...segments);
+}
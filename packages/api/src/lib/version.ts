@@ -0,0 +1,5 @@
+// Reported by /health so the desktop shell can verify it is running a
+// backend it understands. Bump API_PROTOCOL on breaking changes to the
+// HTTP API the shell or frontend relies on.
+export const API_VERSION = '1.0.0';
+export const API_PROTOCOL = 1;
@@ -1,13 +1,13 @@
 /**
  * MCP Server Manager
  *
- * Loads MCP server configuration from ~/.openasst/mcp.json
+ * Loads MCP server configuration from mcp.json in the data directory
+ * (~/.openasst unless OPENASST_DATA_DIR is set)
  * Supports three transport types: stdio, http, sse
  */
 
 import fs from 'fs/promises';
-import { homedir } from 'os';
-import { join } from 'path';
+import { dataPath } from '../lib/data-dir.js';
 
 // ── MCP Server Config Types ──
 
@@ -58,7 +58,7 @@ export interface McpToggleConfig {
  * Get the path to the MCP config file
  */
 export function getMcpConfigPath(): string {
-  return join(homedir(), '.openasst', 'mcp.json');
+  return dataPath('mcp.json');
 }
 
 /**
@@ -113,7 +113,7 @@ async function loadFromFile(
 }
 
 /**
- * Load all configured MCP servers from ~/.openasst/mcp.json
+ * Load all configured MCP servers from mcp.json in the data directory
  *
  * @param mcpConfig - Optional toggle; if enabled is false, returns empty
  * @returns Record of server name to McpServerConfig
@@ -0,0 +1,29 @@
+import { timingSafeEqual } from 'node:crypto';
+import type { Context, Next } from 'hono';
+
+const TOKEN_HEADER = 'X-OpenAsst-Token';
+
+/**
+ * Requires the per-launch secret the desktop shell passes in
+ * OPENASST_API_TOKEN on every request, so other local processes and web pages
+ * cannot drive the API. Disabled when the variable is unset (dev server,
+ * Docker deployments). Shared deployments can set it to a long-lived secret,
+ * which desktop apps using the server as a remote backend send instead.
+ * /health stays open for the shell's readiness probe.
+ */
+export function shellTokenMiddleware(token: string | undefined) {
+  const expected = token ? Buffer.from(token) : null;
+
+  return async (c: Context, next: Next) => {
+    if (!expected || c.req.method === 'OPTIONS' || c.req.path === '/health') {
+      return next();
+    }
+
+    const provided = Buffer.from(c.req.header(TOKEN_HEADER) ?? '');
+    if (provided.length !== expected.length || !timingSafeEqual(provided, expected)) {
+      return c.json({ error: 'Unauthorized' }, 401);
+    }
+
+    return next();
+  };
+}
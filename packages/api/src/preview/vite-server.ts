@@ -1,5 +1,7 @@
-import { spawn, type ChildProcess } from 'child_process';
+import { spawn, spawnSync, type ChildProcess } from 'child_process';
 import { existsSync } from 'fs';
+import { join } from 'path';
+import { CACHE_DIR } from '../lib/data-dir.js';
 
 interface PreviewServer {
   taskId: string;
@@ -9,7 +11,52 @@ interface PreviewServer {
 }
 
 const servers = new Map<string, PreviewServer>();
-let nextPort = 3100;
+// Preview ports; the desktop app's CSP (frame-src in tauri.conf.json) lists
+// each one, so keep the two in sync.
+const FIRST_PORT = 3100;
+const LAST_PORT = 3109;
+
+// First preview port no running preview holds.
+function freePort(): number | undefined {
+  const used = new Set(Array.from(servers.values(), (server) => server.port));
+  for (let port = FIRST_PORT; port <= LAST_PORT; port++) {
+    if (!used.has(port)) return port;
+  }
+  return undefined;
+}
+
+export function listPreviews() {
+  return Array.from(servers.values()).map(({ taskId, port, url }) => ({ taskId, port, url }));
+}
+
+// Pids of every process below `pid`, children before their own children.
+function descendants(pid: number): number[] {
+  const result = spawnSync('pgrep', ['-P', String(pid)], { encoding: 'utf8' });
+  const children = (result.stdout ?? '')
+    .split('\n')
+    .map((line) => Number.parseInt(line, 10))
+    .filter((child) => !Number.isNaN(child));
+  return children.flatMap((child) => [child, ...descendants(child)]);
+}
+
+// Vite runs under a shell, so killing only the direct child would orphan it.
+// Previews stay in the API server's process group so the desktop shell's
+// group kill reaches them even if the API dies without cleaning up; stopping
+// one preview therefore walks its tree instead. On Windows taskkill does that.
+function killTree(child: ChildProcess): void {
+  if (child.pid === undefined) return;
+  if (process.platform === 'win32') {
+    spawnSync('taskkill', ['/pid', String(child.pid), '/T', '/F']);
+    return;
+  }
+  for (const pid of [child.pid, ...descendants(child.pid)]) {
+    try {
+      process.kill(pid, 'SIGTERM');
+    } catch {
+      // Already gone
+    }
+  }
+}
 
 export function getPreviewStatus(taskId: string) {
   const server = servers.get(taskId);
@@ -28,11 +75,16 @@ export async function startPreview(
     throw new Error(`Directory not found: ${workDir}`);
   }
 
-  const port = nextPort++;
-  if (nextPort > 3200) nextPort = 3100;
+  const port = freePort();
+  if (port === undefined) {
+    const count = LAST_PORT - FIRST_PORT + 1;
+    throw new Error(`All ${count} preview ports are in use; stop a preview to start another`);
+  }
 
-  const child = spawn('npx', ['vite', '--port', String(port), '--host'], {
+  const child = spawn('npx', ['vite', '--port', String(port), '--strictPort', '--host'], {
     cwd: workDir,
+    // Keep the packages npx fetches with the rest of the OpenAsst cache
+    env: CACHE_DIR ? { ...process.env, npm_config_cache: join(CACHE_DIR, 'npm') } : process.env,
     stdio: 'pipe',
     shell: true,
   });
@@ -59,14 +111,14 @@ export async function startPreview(
 export function stopPreview(taskId: string): boolean {
   const server = servers.get(taskId);
   if (!server) return false;
-  server.process.kill();
+  killTree(server.process);
   servers.delete(taskId);
   return true;
 }
 
 export function stopAllPreviews(): void {
   for (const [id, server] of servers) {
-    server.process.kill();
+    killTree(server.process);
     servers.delete(id);
   }
 }
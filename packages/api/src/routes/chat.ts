@@ -6,8 +6,8 @@ import { DeviceManager } from '../server-mgmt/device-manager.js';
 import { KnowledgeManager } from '../knowledge/manager.js';
 import type { AgentRequest } from '@openasst/types';
 import { homedir } from 'os';
-import { join } from 'path';
 import { mkdirSync } from 'fs';
+import { dataPath } from '../lib/data-dir.js';
 
 export const agentRoutes = new Hono();
 
@@ -49,7 +49,7 @@ agentRoutes.post('/', async (c) => {
   }
 
   const workDir = body.workDir
-    || join(homedir(), '.openasst', 'sessions', taskId || 'default');
+    || dataPath('sessions', taskId || 'default');
   mkdirSync(workDir, { recursive: true });
 
   let finalPrompt = deviceId ? buildDevicePrompt(prompt, deviceId) : prompt;
@@ -79,7 +79,7 @@ agentRoutes.post('/plan', async (c) => {
   if (!prompt) return c.json({ error: 'prompt is required' }, 400);
 
   const workDir = body.workDir
-    || join(homedir(), '.openasst', 'sessions', taskId || 'default');
+    || dataPath('sessions', taskId || 'default');
   mkdirSync(workDir, { recursive: true });
 
   const generator = planAgent(prompt, workDir, modelConfig);
@@ -99,7 +99,7 @@ agentRoutes.post('/execute', async (c) => {
   if (!execPrompt) return c.json({ error: 'prompt or planId required' }, 400);
 
   const workDir = body.workDir
-    || join(homedir(), '.openasst', 'sessions', taskId || 'default');
+    || dataPath('sessions', taskId || 'default');
   mkdirSync(workDir, { recursive: true });
 
   if (planId) deletePlan(planId);
@@ -2,7 +2,7 @@
  * MCP Config Routes
  *
  * Hono routes for MCP server configuration CRUD.
- * Config is persisted at ~/.openasst/mcp.json
+ * Config is persisted at mcp.json in the data directory
  */
 
 import fs from 'fs/promises';
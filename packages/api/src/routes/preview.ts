@@ -4,6 +4,7 @@ import {
   stopPreview,
   stopAllPreviews,
   getPreviewStatus,
+  listPreviews,
 } from '../preview/vite-server.js';
 
 export const previewRoutes = new Hono();
@@ -27,6 +28,10 @@ previewRoutes.post('/stop', async (c) => {
   return c.json({ stopped: stopPreview(taskId) });
 });
 
+previewRoutes.get('/status', (c) => {
+  return c.json({ previews: listPreviews() });
+});
+
 previewRoutes.get('/status/:taskId', (c) => {
   const { taskId } = c.req.param();
   return c.json(getPreviewStatus(taskId));
@@ -2,13 +2,13 @@ import { Hono } from 'hono';
 import { streamSSE } from 'hono/streaming';
 import { readFileSync, writeFileSync, existsSync, mkdirSync } from 'fs';
 import { join } from 'path';
-import { homedir } from 'os';
 import { DeviceManager } from '../server-mgmt/device-manager.js';
 import { CommandExecutor } from '../server-mgmt/executor.js';
 import { deployOpenClaw, buildSshCmd } from '../openclaw/deployer.js';
+import { workspacePath } from '../lib/data-dir.js';
 import type { DeployConfig } from '../openclaw/deployer.js';
 
-const botsDir = join(homedir(), 'openasst', 'bots');
+const botsDir = workspacePath('bots');
 const deviceMgr = new DeviceManager();
 const executor = new CommandExecutor();
 
@@ -1,7 +1,7 @@
 import { Hono } from 'hono';
-import { homedir } from 'os';
 import { join } from 'path';
 import { readFileSync, writeFileSync, mkdirSync, existsSync } from 'fs';
+import { DATA_DIR } from '../lib/data-dir.js';
 
 export const settingsRoutes = new Hono();
 
@@ -12,7 +12,7 @@ interface Settings {
   [key: string]: unknown;
 }
 
-const SETTINGS_DIR = join(homedir(), '.openasst');
+const SETTINGS_DIR = DATA_DIR;
 const SETTINGS_FILE = join(SETTINGS_DIR, 'config.json');
 
 const DEFAULT_SETTINGS: Settings = {
@@ -1,8 +1,9 @@
 import * as fs from 'fs';
 import * as path from 'path';
 import * as os from 'os';
+import { DATA_DIR, dataPath } from '../lib/data-dir.js';
 
-const CONFIG_FILE = path.join(os.homedir(), '.openasst', 'config.json');
+const CONFIG_FILE = dataPath('config.json');
 
 function readConfig(): { apiKey?: string; baseUrl?: string; model?: string } | null {
   try {
@@ -83,7 +84,7 @@ export class APIProvider {
   };
 
   constructor() {
-    this.configDir = path.join(os.homedir(), '.openasst');
+    this.configDir = DATA_DIR;
     this.sharedConfigPath = path.join(this.configDir, 'shared-api.json');
   }
 
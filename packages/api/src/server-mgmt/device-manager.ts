@@ -3,8 +3,9 @@ import * as fs from 'fs';
 import * as path from 'path';
 import * as os from 'os';
 import * as crypto from 'crypto';
+import { DATA_DIR } from '../lib/data-dir.js';
 
-const CONFIG_DIR = path.join(os.homedir(), '.openasst');
+const CONFIG_DIR = DATA_DIR;
 const DEVICES_FILE = path.join(CONFIG_DIR, 'devices.json');
 
 export type ConnectionType = 'ssh' | 'local' | 'docker' | 'docker-remote' | 'kubernetes' | 'wsl';
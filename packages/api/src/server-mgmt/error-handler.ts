@@ -1,10 +1,9 @@
 import Anthropic from '@anthropic-ai/sdk';
 import * as fs from 'fs';
-import * as path from 'path';
-import * as os from 'os';
 import { ExecutionLog } from './executor.js';
+import { dataPath } from '../lib/data-dir.js';
 
-const CONFIG_FILE = path.join(os.homedir(), '.openasst', 'config.json');
+const CONFIG_FILE = dataPath('config.json');
 
 function readConfig(): { apiKey?: string; baseUrl?: string; model?: string } {
   try {
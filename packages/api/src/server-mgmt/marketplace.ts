@@ -1,8 +1,7 @@
 import * as fs from 'fs';
 import * as path from 'path';
-import * as os from 'os';
+import { DATA_DIR } from '../lib/data-dir.js';
 
-const DATA_DIR = path.join(os.homedir(), '.openasst');
 const SCRIPTS_FILE = path.join(DATA_DIR, 'scripts.json');
 const DEFAULT_API_URL = 'https://openasst.ai';
 
@@ -1,7 +1,7 @@
 import * as fs from 'fs';
 import * as path from 'path';
-import * as os from 'os';
 import { CommandExecutor } from './executor.js';
+import { DATA_DIR } from '../lib/data-dir.js';
 
 export interface ScheduledTask {
   id: string;
@@ -20,7 +20,7 @@ export class Scheduler {
   private executor: CommandExecutor;
 
   constructor() {
-    const configDir = path.join(os.homedir(), '.openasst');
+    const configDir = DATA_DIR;
     this.configPath = path.join(configDir, 'schedules.json');
     this.executor = new CommandExecutor();
     this.ensureConfig();
@@ -1,7 +1,7 @@
 import { CommandExecutor } from './executor.js';
 import * as fs from 'fs';
 import * as path from 'path';
-import * as os from 'os';
+import { dataPath, LOG_DIR } from '../lib/data-dir.js';
 
 export interface ServiceInfo {
   name: string;
@@ -20,13 +20,13 @@ export class ServiceManager {
 
   constructor() {
     this.executor = new CommandExecutor();
-    this.servicesFile = path.join(os.homedir(), '.openasst', 'services.json');
+    this.servicesFile = dataPath('services.json');
     this.loadServices();
   }
 
   async start(name: string, command: string, workDir?: string): Promise<boolean> {
     const dir = workDir || process.cwd();
-    const logFile = path.join(os.homedir(), '.openasst', 'logs', `${name}.log`);
+    const logFile = path.join(LOG_DIR, `${name}.log`);
 
     const logDir = path.dirname(logFile);
     if (!fs.existsSync(logDir)) {
@@ -1,7 +1,7 @@
 import * as fs from 'fs';
 import * as path from 'path';
-import * as os from 'os';
 import { CommandExecutor } from './executor.js';
+import { dataPath } from '../lib/data-dir.js';
 
 export interface Skill {
   id: string;
@@ -34,7 +34,7 @@ export class SkillManager {
   private executor: CommandExecutor;
 
   constructor() {
-    this.skillsDir = path.join(os.homedir(), '.openasst', 'skills');
+    this.skillsDir = dataPath('skills');
     this.skillsIndexPath = path.join(this.skillsDir, 'index.json');
     this.executor = new CommandExecutor();
     this.ensureSkillsDir();
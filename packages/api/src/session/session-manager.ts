@@ -1,9 +1,9 @@
 import * as fs from 'fs';
 import * as path from 'path';
-import * as os from 'os';
 import * as crypto from 'crypto';
+import { dataPath } from '../lib/data-dir.js';
 
-const SESSIONS_DIR = path.join(os.homedir(), '.openasst', 'sessions');
+const SESSIONS_DIR = dataPath('sessions');
 const INDEX_FILE = path.join(SESSIONS_DIR, 'index.json');
 
 export interface SessionMeta {
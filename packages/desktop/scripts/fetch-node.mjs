@@ -0,0 +1,101 @@
+#!/usr/bin/env node
+
+// Downloads the pinned Node.js runtime that the desktop app bundles as a
+// Tauri external binary, verifies it against the official SHASUMS256.txt,
+// and writes src-tauri/binaries/node-<target-triple>[.exe] together with a
+// .sha256 file that build.rs embeds for the startup integrity check.
+
+import { createHash } from 'node:crypto';
+import { execFileSync } from 'node:child_process';
+import fs from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { fileURLToPath } from 'node:url';
+
+const NODE_VERSION = '20.18.1';
+
+const PLATFORMS = {
+  'x86_64-unknown-linux-gnu': { dist: 'linux-x64', ext: 'tar.xz' },
+  'aarch64-unknown-linux-gnu': { dist: 'linux-arm64', ext: 'tar.xz' },
+  'x86_64-apple-darwin': { dist: 'darwin-x64', ext: 'tar.gz' },
+  'aarch64-apple-darwin': { dist: 'darwin-arm64', ext: 'tar.gz' },
+  'x86_64-pc-windows-msvc': { dist: 'win-x64', ext: 'zip' },
+  'aarch64-pc-windows-msvc': { dist: 'win-arm64', ext: 'zip' },
+};
+
+const binariesDir = path.join(path.dirname(fileURLToPath(import.meta.url)), '..', 'src-tauri', 'binaries');
+
+function targetTriple() {
+  if (process.env.TAURI_ENV_TARGET_TRIPLE) return process.env.TAURI_ENV_TARGET_TRIPLE;
+  const info = execFileSync('rustc', ['-vV'], { encoding: 'utf8' });
+  return info.match(/^host: (\S+)$/m)[1];
+}
+
+function sha256(data) {
+  return createHash('sha256').update(data).digest('hex');
+}
+
+async function download(url) {
+  const res = await fetch(url);
+  if (!res.ok) throw new Error(`GET ${url} failed: ${res.status}`);
+  return Buffer.from(await res.arrayBuffer());
+}
+
+async function main() {
+  const triple = targetTriple();
+  const platform = PLATFORMS[triple];
+  if (!platform) throw new Error(`No Node.js build for target ${triple}`);
+
+  const windows = platform.dist.startsWith('win');
+  const output = path.join(binariesDir, `node-${triple}${windows ? '.exe' : ''}`);
+  const checksumFile = path.join(binariesDir, `node-${triple}.sha256`);
+
+  if (fs.existsSync(output) && fs.existsSync(checksumFile)) {
+    const recorded = fs.readFileSync(checksumFile, 'utf8').trim();
+    if (sha256(fs.readFileSync(output)) === recorded) {
+      console.log(`Node.js ${NODE_VERSION} for ${triple} already present`);
+      return;
+    }
+  }
+
+  const base = `https://nodejs.org/dist/v${NODE_VERSION}`;
+  const archiveName = `node-v${NODE_VERSION}-${platform.dist}.${platform.ext}`;
+
+  console.log(`Downloading ${archiveName}...`);
+  const [sums, archive] = await Promise.all([
+    download(`${base}/SHASUMS256.txt`).then((b) => b.toString('utf8')),
+    download(`${base}/${archiveName}`),
+  ]);
+
+  const expected = sums
+    .split('\n')
+    .map((line) => line.trim().split(/\s+/))
+    .find(([, name]) => name === archiveName)?.[0];
+  if (!expected) throw new Error(`${archiveName} is not listed in SHASUMS256.txt`);
+  if (sha256(archive) !== expected) throw new Error(`Checksum mismatch for ${archiveName}`);
+
+  const tmp = fs.mkdtempSync(path.join(os.tmpdir(), 'openasst-node-'));
+  try {
+    const archivePath = path.join(tmp, archiveName);
+    fs.writeFileSync(archivePath, archive);
+    // bsdtar (macOS, Windows 10+) and GNU tar both handle these formats.
+    execFileSync('tar', ['-xf', archivePath, '-C', tmp]);
+
+    const root = path.join(tmp, `node-v${NODE_VERSION}-${platform.dist}`);
+    const binary = windows ? path.join(root, 'node.exe') : path.join(root, 'bin', 'node');
+
+    fs.mkdirSync(binariesDir, { recursive: true });
+    fs.copyFileSync(binary, output);
+    fs.chmodSync(output, 0o755);
+    fs.writeFileSync(checksumFile, `${sha256(fs.readFileSync(output))}\n`);
+  } finally {
+    fs.rmSync(tmp, { recursive: true, force: true });
+  }
+
+  console.log(`Bundled Node.js ${NODE_VERSION} at ${path.relative(process.cwd(), output)}`);
+}
+
+main().catch((err) => {
+  console.error(err.message);
+  process.exit(1);
+});
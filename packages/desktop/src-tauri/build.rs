@@ -1,3 +1,19 @@
 fn main() {
+    // Embed the checksum of the bundled Node runtime written by
+    // `scripts/fetch-node.mjs`, so the shell can verify it at startup.
+    let target = std::env::var("TARGET").unwrap();
+    let checksum_file = format!("binaries/node-{}.sha256", target);
+    println!("cargo:rerun-if-changed={}", checksum_file);
+    let checksum = match std::fs::read_to_string(&checksum_file) {
+        Ok(checksum) => checksum,
+        // A release must never ship a runtime it cannot verify
+        Err(e) if std::env::var("PROFILE").as_deref() == Ok("release") => panic!(
+            "{} is required for release builds ({}); run `pnpm fetch-node` first",
+            checksum_file, e
+        ),
+        Err(_) => String::new(),
+    };
+    println!("cargo:rustc-env=OPENASST_NODE_SHA256={}", checksum.trim());
+
     tauri_build::build()
 }
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager, Window};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogResult};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::logs;
+use crate::settings::{self, SettingsStore};
+use crate::{tray, windows};
+
+const KEEP_RUNNING: &str = "Keep Running";
+const QUIT: &str = "Quit";
+
+/// Whether the "still running" notice was shown during this run.
+static TRAY_NOTICE_SHOWN: AtomicBool = AtomicBool::new(false);
+
+/// What closing the main window does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CloseBehavior {
+    /// Hide the window; agent tasks and hub connections keep running.
+    Tray,
+    /// Quit the app, stopping the API server.
+    Quit,
+    /// Ask every time.
+    #[default]
+    Ask,
+}
+
+/// Handles a close request for the main window. The close itself is always
+/// prevented: hiding leaves the window in place, and quitting goes through
+/// [`AppHandle::exit`] so the hidden quick prompt window does not keep the
+/// app alive.
+pub fn on_close_requested(window: &Window, api: &tauri::CloseRequestApi) {
+    api.prevent_close();
+    let app = window.app_handle();
+    match settings::current(app).close_behavior {
+        CloseBehavior::Tray => hide_to_tray(app, window),
+        CloseBehavior::Quit => app.exit(0),
+        CloseBehavior::Ask => ask(app, window),
+    }
+}
+
+/// Asks whether to keep running. The first answer becomes the setting, so
+/// the question comes up once unless "Ask" is chosen again in the tray menu.
+fn ask(app: &AppHandle, window: &Window) {
+    let first_time = !settings::current(app).close_prompt_shown;
+    let mut message = "OpenAsst can keep running in the system tray so agent tasks and \
+                       hub connections carry on while the window is closed. Quitting \
+                       stops them."
+        .to_string();
+    if first_time {
+        message.push_str(
+            "\n\nYour choice will be remembered and can be changed from the tray icon's menu, \
+             under \"When the Window Closes\".",
+        );
+    }
+
+    let app = app.clone();
+    let window = window.clone();
+    app.dialog()
+        .message(message)
+        .title("Close OpenAsst")
+        .parent(&window)
+        .buttons(MessageDialogButtons::YesNoCancelCustom(
+            KEEP_RUNNING.to_string(),
+            QUIT.to_string(),
+            "Cancel".to_string(),
+        ))
+        .show_with_result(move |result| {
+            let behavior = match result {
+                MessageDialogResult::Custom(label) if label == KEEP_RUNNING => CloseBehavior::Tray,
+                MessageDialogResult::Custom(label) if label == QUIT => CloseBehavior::Quit,
+                MessageDialogResult::Yes => CloseBehavior::Tray,
+                MessageDialogResult::No => CloseBehavior::Quit,
+                _ => return,
+            };
+            if first_time {
+                set_behavior(&app, behavior);
+            }
+            match behavior {
+                CloseBehavior::Quit => app.exit(0),
+                _ => hide_to_tray(&app, &window),
+            }
+        });
+}
+
+/// Saves what closing the main window does from now on.
+pub fn set_behavior(app: &AppHandle, behavior: CloseBehavior) {
+    let store = app.state::<SettingsStore>();
+    let mut settings = store.get();
+    settings.close_behavior = behavior;
+    settings.close_prompt_shown = true;
+    if let Err(e) = store.set(settings) {
+        logs::error(app, &format!("Failed to save settings: {}", e));
+    }
+    tray::set_close_behavior(app);
+}
+
+/// Hides the window and, once per run, tells the user the app is still there.
+/// Headless, the window is destroyed instead to free its webview; the tray
+/// creates it again.
+fn hide_to_tray(app: &AppHandle, window: &Window) {
+    if windows::is_headless(app) {
+        window.destroy().unwrap_or_default();
+    } else {
+        window.hide().unwrap_or_default();
+    }
+    if TRAY_NOTICE_SHOWN.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("OpenAsst is still running")
+        .body("Agent tasks and hub connections stay active. Use the tray icon to reopen or quit.")
+        .show()
+    {
+        logs::warn(app, &format!("Failed to show notification: {}", e));
+    }
+}
@@ -0,0 +1,259 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::cli::Cli;
+use crate::{logs, tray};
+
+/// URL scheme registered for the app (see `plugins.deep-link` in tauri.conf.json).
+pub const SCHEME: &str = "openasst";
+
+/// Event telling the frontend a link is waiting; it takes the link with
+/// [`take_pending_deep_link`].
+pub const DEEP_LINK_EVENT: &str = "deep-link://open";
+
+/// Longest id accepted in a link; ids are generated by the app and far shorter.
+const MAX_ID_LEN: usize = 128;
+
+/// A validated `openasst://` link.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DeepLink {
+    /// `openasst://session/<id>`
+    Session { id: String },
+    /// `openasst://device/<id>/terminal`
+    Device { id: String, view: DeviceView },
+    /// `openasst://marketplace/script/<id>`
+    MarketplaceScript { id: String },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeviceView {
+    Terminal,
+}
+
+impl DeepLink {
+    pub fn session(id: &str) -> Result<Self, String> {
+        Ok(DeepLink::Session { id: valid_id(id)? })
+    }
+
+    /// Parses and validates a link. Everything about the URL beyond the
+    /// recognised route must be absent, so links cannot smuggle parameters.
+    pub fn parse(url: &Url) -> Result<Self, String> {
+        if url.scheme() != SCHEME {
+            return Err(format!("unsupported scheme {}", url.scheme()));
+        }
+        if url.query().is_some() || url.fragment().is_some() {
+            return Err("query strings and fragments are not supported".to_string());
+        }
+
+        // `openasst://session/<id>` has host `session` and path `/<id>`.
+        let host = url.host_str().unwrap_or_default();
+        let segments: Vec<&str> = url
+            .path()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+
+        match (host, segments.as_slice()) {
+            ("session", [id]) => DeepLink::session(id),
+            ("device", [id]) | ("device", [id, "terminal"]) => Ok(DeepLink::Device {
+                id: valid_id(id)?,
+                view: DeviceView::Terminal,
+            }),
+            ("marketplace", ["script", id]) => {
+                Ok(DeepLink::MarketplaceScript { id: valid_id(id)? })
+            }
+            _ => Err("unknown route".to_string()),
+        }
+    }
+}
+
+fn valid_id(id: &str) -> Result<String, String> {
+    let valid = !id.is_empty()
+        && id.len() <= MAX_ID_LEN
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        // Never a path segment that refers to a parent or itself
+        && id.chars().any(|c| c != '.');
+    if valid {
+        Ok(id.to_string())
+    } else {
+        Err(format!("invalid id {:?}", id))
+    }
+}
+
+/// Managed state holding the latest link until the main window takes it, so
+/// a window that is still loading, or reloading, does not miss it.
+pub struct DeepLinks {
+    pending: Mutex<Option<DeepLink>>,
+}
+
+/// Registers the scheme where that happens at runtime, picks up a link the
+/// app was launched with and forwards links opened while it runs.
+pub fn setup(app: &AppHandle) {
+    // Installers register the scheme; this covers AppImages and dev builds.
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        logs::warn(
+            app,
+            &format!("Failed to register {}:// links: {}", SCHEME, e),
+        );
+    }
+
+    let pending = app
+        .deep_link()
+        .get_current()
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+        .iter()
+        .chain(app.state::<Cli>().open.as_ref())
+        .find_map(|url| accept(app, url));
+    // Headless launches have no window yet to take the link
+    if pending.is_some() {
+        tray::show_main_window(app);
+    }
+    app.manage(DeepLinks {
+        pending: Mutex::new(pending),
+    });
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            open_url(&handle, &url);
+        }
+    });
+}
+
+/// Validates `url` and opens it, ignoring links that do not parse.
+pub fn open_url(app: &AppHandle, url: &Url) {
+    if let Some(link) = accept(app, url) {
+        open(app, link);
+    }
+}
+
+/// Brings up the main window and navigates it to `link`. The link waits
+/// until the frontend takes it, either on the event or once it has loaded.
+pub fn open(app: &AppHandle, link: DeepLink) {
+    *app.state::<DeepLinks>().pending.lock().unwrap() = Some(link);
+    tray::show_main_window(app);
+    let _ = app.emit(DEEP_LINK_EVENT, ());
+}
+
+fn accept(app: &AppHandle, url: &Url) -> Option<DeepLink> {
+    match DeepLink::parse(url) {
+        Ok(link) => {
+            logs::info(app, &format!("Opening link {}", url));
+            Some(link)
+        }
+        Err(e) => {
+            logs::warn(app, &format!("Ignoring link {}: {}", url, e));
+            None
+        }
+    }
+}
+
+/// Returns the waiting link, once.
+#[tauri::command]
+pub fn take_pending_deep_link(links: tauri::State<'_, DeepLinks>) -> Option<DeepLink> {
+    links.pending.lock().unwrap().take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(link: &str) -> Result<DeepLink, String> {
+        DeepLink::parse(&Url::parse(link).unwrap())
+    }
+
+    #[test]
+    fn parses_known_routes() {
+        assert_eq!(
+            parse("openasst://session/abc-123"),
+            Ok(DeepLink::Session {
+                id: "abc-123".to_string()
+            })
+        );
+        assert_eq!(
+            parse("openasst://device/dev_1/terminal"),
+            Ok(DeepLink::Device {
+                id: "dev_1".to_string(),
+                view: DeviceView::Terminal
+            })
+        );
+        assert_eq!(
+            parse("openasst://device/dev_1"),
+            Ok(DeepLink::Device {
+                id: "dev_1".to_string(),
+                view: DeviceView::Terminal
+            })
+        );
+        assert_eq!(
+            parse("openasst://marketplace/script/s.1"),
+            Ok(DeepLink::MarketplaceScript {
+                id: "s.1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_routes() {
+        for link in [
+            "openasst://settings",
+            "openasst://session",
+            "openasst://session/a/b",
+            "openasst://device/a/files",
+            "openasst://marketplace/a",
+            "openasst://marketplace/plugin/a",
+            "openasst:session/a",
+        ] {
+            assert!(parse(link).is_err(), "{}", link);
+        }
+    }
+
+    #[test]
+    fn rejects_other_schemes_and_parameters() {
+        for link in [
+            "https://session/a",
+            "file:///session/a",
+            "openasst://session/a?token=x",
+            "openasst://session/a#x",
+        ] {
+            assert!(parse(link).is_err(), "{}", link);
+        }
+    }
+
+    #[test]
+    fn rejects_hostile_ids() {
+        for link in [
+            "openasst://session/a%2Fb",
+            "openasst://session/a%00",
+            "openasst://session/%3Cscript%3E",
+            "openasst://session/%2E%2E%2Fsettings",
+            "openasst://device/a%20b/terminal",
+        ] {
+            assert!(parse(link).is_err(), "{}", link);
+        }
+        let long = format!("openasst://session/{}", "a".repeat(MAX_ID_LEN + 1));
+        assert!(parse(&long).is_err());
+    }
+
+    #[test]
+    fn dot_segments_do_not_escape_the_route() {
+        // The URL parser resolves them before routing
+        assert_eq!(
+            parse("openasst://session/../a"),
+            Ok(DeepLink::Session {
+                id: "a".to_string()
+            })
+        );
+        assert!(parse("openasst://session/..").is_err());
+        assert!(valid_id(".").is_err());
+        assert!(valid_id("..").is_err());
+    }
+}
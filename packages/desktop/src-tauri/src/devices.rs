@@ -0,0 +1,161 @@
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+use tokio::time::{sleep, Duration};
+
+use crate::deep_link::{self, DeepLink, DeviceView};
+use crate::proxy::{self, Endpoint};
+use crate::sidecar::Supervisor;
+use crate::tray;
+
+/// How often the device list is re-read when nothing changed it through the
+/// shell, e.g. after edits made with the CLI.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A device as listed by `GET /devices`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Device {
+    pub id: String,
+    pub label: String,
+    pub group: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DevicesResponse {
+    devices: Vec<Device>,
+}
+
+#[derive(Deserialize)]
+struct Group {
+    name: String,
+    #[serde(default)]
+    devices: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct GroupsResponse {
+    groups: Vec<Group>,
+}
+
+/// Devices arranged for the tray: named groups in alphabetical order, then
+/// devices that belong to no group.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeviceTree {
+    pub groups: BTreeMap<String, Vec<Device>>,
+    pub ungrouped: Vec<Device>,
+}
+
+impl DeviceTree {
+    /// Places each device in its own `group` and in every device group that
+    /// lists it.
+    fn build(devices: Vec<Device>, groups: Vec<Group>) -> Self {
+        let mut tree = DeviceTree::default();
+        for group in &groups {
+            tree.groups.entry(group.name.clone()).or_default();
+        }
+        for device in devices {
+            let mut names: Vec<&str> = groups
+                .iter()
+                .filter(|g| g.devices.contains(&device.id))
+                .map(|g| g.name.as_str())
+                .collect();
+            if let Some(group) = device.group.as_deref().filter(|g| !g.is_empty()) {
+                if !names.contains(&group) {
+                    names.push(group);
+                }
+            }
+            if names.is_empty() {
+                tree.ungrouped.push(device);
+                continue;
+            }
+            for name in names {
+                tree.groups
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(device.clone());
+            }
+        }
+        tree.groups.retain(|_, devices| !devices.is_empty());
+        tree
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty() && self.ungrouped.is_empty()
+    }
+}
+
+/// Managed state holding the last device list shown in the tray.
+pub struct DeviceMonitor {
+    tree: Mutex<Option<DeviceTree>>,
+    changed: Notify,
+}
+
+impl DeviceMonitor {
+    pub fn new() -> Self {
+        Self {
+            tree: Mutex::new(None),
+            changed: Notify::new(),
+        }
+    }
+
+    /// Asks [`watch`] to re-read the device list now.
+    pub fn invalidate(&self) {
+        self.changed.notify_one();
+    }
+
+    async fn fetch(endpoint: &Endpoint) -> Result<DeviceTree, String> {
+        let devices: DevicesResponse = get_json(endpoint, "/devices").await?;
+        // Groups are optional; older servers only have the per-device field.
+        let groups = get_json::<GroupsResponse>(endpoint, "/devices/groups")
+            .await
+            .map(|response| response.groups)
+            .unwrap_or_default();
+        Ok(DeviceTree::build(devices.devices, groups))
+    }
+}
+
+async fn get_json<T: DeserializeOwned>(endpoint: &Endpoint, path: &str) -> Result<T, String> {
+    tokio::time::timeout(REQUEST_TIMEOUT, proxy::get_json(endpoint, path))
+        .await
+        .map_err(|_| "request timed out".to_string())?
+}
+
+/// Keeps the tray's device submenu in sync with the API. Refreshes when the
+/// webview changes devices through the shell and on a slow poll otherwise.
+/// While the API is unreachable the last known list stays in place.
+pub async fn watch(app: AppHandle) {
+    let monitor = app.state::<DeviceMonitor>();
+    let supervisor = app.state::<Supervisor>();
+    loop {
+        if let Ok(tree) = DeviceMonitor::fetch(supervisor.endpoint()).await {
+            let changed = {
+                let mut current = monitor.tree.lock().unwrap();
+                let changed = current.as_ref() != Some(&tree);
+                *current = Some(tree.clone());
+                changed
+            };
+            if changed {
+                tray::set_devices(&app, &tree);
+            }
+        }
+        tokio::select! {
+            _ = sleep(POLL_INTERVAL) => {}
+            _ = monitor.changed.notified() => {}
+        }
+    }
+}
+
+/// Brings up the main window and asks it to open the device's terminal.
+pub fn open(app: &AppHandle, device_id: &str) {
+    deep_link::open(
+        app,
+        DeepLink::Device {
+            id: device_id.to_string(),
+            view: DeviceView::Terminal,
+        },
+    );
+}
@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::sync::Mutex;
+use tauri::http::StatusCode;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::{sleep, Duration};
+
+use crate::proxy::{self, Endpoint};
+use crate::sidecar::Supervisor;
+use crate::tray;
+
+/// Event emitted to the frontend whenever the API health changes.
+pub const HEALTH_EVENT: &str = "sidecar://health";
+
+/// Value of `service` in `/health`, identifying the OpenAsst API.
+const SERVICE_NAME: &str = "openasst-api";
+
+/// Version of this shell; the bundled API is expected to match it.
+const SHELL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// `protocol` values of the API this shell can talk to.
+const SUPPORTED_PROTOCOLS: RangeInclusive<u32> = 1..=1;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HealthResponse {
+    status: String,
+    service: Option<String>,
+    version: Option<String>,
+    protocol: Option<u32>,
+    #[serde(default)]
+    claude_code: bool,
+}
+
+/// Structured result of the last `/health` probe.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiHealth {
+    pub healthy: bool,
+    /// Whether the API found a Claude Code installation.
+    pub claude_code: bool,
+    pub version: Option<String>,
+    /// The API speaks a protocol this shell does not support.
+    pub incompatible: bool,
+    /// Set when the API runs in degraded mode, e.g. a version mismatch
+    /// within the supported protocol range.
+    pub warning: Option<String>,
+    pub error: Option<String>,
+}
+
+impl ApiHealth {
+    pub fn down(error: impl Into<String>) -> Self {
+        Self {
+            error: Some(error.into()),
+            ..Self::default()
+        }
+    }
+
+    /// One-line summary for the tray.
+    pub fn summary(&self) -> String {
+        match (&self.error, &self.warning, self.claude_code) {
+            (Some(error), _, _) => format!("API unavailable: {}", error),
+            (None, Some(warning), _) => format!("API degraded: {}", warning),
+            (None, None, true) => "API healthy".to_string(),
+            (None, None, false) => "API healthy (Claude Code not found)".to_string(),
+        }
+    }
+}
+
+/// Managed state holding the last known health.
+pub struct HealthMonitor {
+    health: Mutex<ApiHealth>,
+}
+
+impl HealthMonitor {
+    pub fn new() -> Self {
+        Self {
+            health: Mutex::new(ApiHealth::down("not checked yet")),
+        }
+    }
+
+    pub fn health(&self) -> ApiHealth {
+        self.health.lock().unwrap().clone()
+    }
+
+    /// Records `health`, notifying the frontend and tray when it changed.
+    pub fn update(&self, app: &AppHandle, health: ApiHealth) {
+        {
+            let mut current = self.health.lock().unwrap();
+            if *current == health {
+                return;
+            }
+            *current = health.clone();
+        }
+        tray::refresh(app);
+        let _ = app.emit(HEALTH_EVENT, health);
+    }
+
+    /// Calls `GET /health` and checks that the responder is the OpenAsst API.
+    pub async fn check(&self, endpoint: &Endpoint) -> ApiHealth {
+        let (status, body) =
+            match tokio::time::timeout(REQUEST_TIMEOUT, proxy::get(endpoint, "/health")).await {
+                Ok(Ok(response)) => response,
+                Ok(Err(e)) => return ApiHealth::down(e),
+                Err(_) => return ApiHealth::down("request timed out"),
+            };
+
+        if !status.is_success() {
+            return ApiHealth::down(format!("HTTP {}", status));
+        }
+
+        let body: HealthResponse = match serde_json::from_slice(&body) {
+            Ok(body) => body,
+            Err(_) => return ApiHealth::down("unexpected /health response"),
+        };
+
+        if body.service.as_deref() != Some(SERVICE_NAME) {
+            return ApiHealth::down("endpoint is served by another application");
+        }
+        if body.status != "ok" {
+            return ApiHealth::down(format!("status {}", body.status));
+        }
+
+        if !body
+            .protocol
+            .is_some_and(|p| SUPPORTED_PROTOCOLS.contains(&p))
+        {
+            return ApiHealth {
+                version: body.version,
+                incompatible: true,
+                ..ApiHealth::down(format!(
+                    "API server protocol {} is not supported by OpenAsst {} (supports {}-{})",
+                    body.protocol
+                        .map_or_else(|| "unknown".to_string(), |p| p.to_string()),
+                    SHELL_VERSION,
+                    SUPPORTED_PROTOCOLS.start(),
+                    SUPPORTED_PROTOCOLS.end()
+                ))
+            };
+        }
+
+        let warning = match body.version.as_deref() {
+            Some(SHELL_VERSION) => None,
+            Some(version) => Some(format!(
+                "API server {} does not match OpenAsst {}",
+                version, SHELL_VERSION
+            )),
+            None => Some("API server did not report a version".to_string()),
+        };
+
+        ApiHealth {
+            healthy: true,
+            claude_code: body.claude_code,
+            version: body.version,
+            incompatible: false,
+            warning,
+            error: None,
+        }
+    }
+}
+
+/// Checks a remote server before the shell switches to it: `/health` must
+/// report a compatible OpenAsst API, and the token must be accepted by the
+/// routes behind it.
+pub async fn check_remote(app: &AppHandle, endpoint: &Endpoint) -> Result<(), String> {
+    let health = app.state::<HealthMonitor>().check(endpoint).await;
+    if !health.healthy {
+        return Err(health.summary());
+    }
+    match tokio::time::timeout(REQUEST_TIMEOUT, proxy::get(endpoint, "/hub/status")).await {
+        Ok(Ok((StatusCode::UNAUTHORIZED, _))) => Err("The server rejected the token".to_string()),
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(e),
+        Err(_) => Err("request timed out".to_string()),
+    }
+}
+
+#[tauri::command]
+pub fn get_api_health(monitor: tauri::State<'_, HealthMonitor>) -> ApiHealth {
+    monitor.health()
+}
+
+/// Polls `/health` until it reports healthy or incompatible, giving up
+/// after ~15 seconds. Returns the last result.
+pub async fn wait_until_healthy(app: &AppHandle) -> ApiHealth {
+    let monitor = app.state::<HealthMonitor>();
+    let supervisor = app.state::<Supervisor>();
+    let mut health = ApiHealth::down("not checked yet");
+    for _ in 0..30 {
+        sleep(Duration::from_millis(500)).await;
+        health = monitor.check(supervisor.endpoint()).await;
+        monitor.update(app, health.clone());
+        if health.healthy || health.incompatible {
+            break;
+        }
+    }
+    health
+}
+
+/// Re-checks `/health` every few seconds for as long as it is polled.
+pub async fn watch(app: &AppHandle) {
+    let monitor = app.state::<HealthMonitor>();
+    let supervisor = app.state::<Supervisor>();
+    loop {
+        let health = monitor.check(supervisor.endpoint()).await;
+        monitor.update(app, health);
+        sleep(POLL_INTERVAL).await;
+    }
+}
@@ -0,0 +1,147 @@
+use serde::Deserialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::Notify;
+use tokio::time::{sleep, Duration};
+
+use crate::logs;
+use crate::proxy;
+use crate::sidecar::Supervisor;
+use crate::tray;
+
+/// Matches the polling interval of the hub panel in the UI.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Agent {
+    pub name: String,
+}
+
+/// Response of `GET /hub/status`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct HubStatus {
+    pub running: bool,
+    pub port: u16,
+    pub agents: Vec<Agent>,
+}
+
+impl HubStatus {
+    /// One-line summary for the tray.
+    pub fn summary(&self) -> String {
+        match (self.running, self.agents.len()) {
+            (false, _) => "Hub: stopped".to_string(),
+            (true, 1) => format!("Hub: running on port {}, 1 agent", self.port),
+            (true, n) => format!("Hub: running on port {}, {} agents", self.port, n),
+        }
+    }
+}
+
+/// Managed state holding the last known hub status. `None` while the API is
+/// unreachable.
+pub struct HubMonitor {
+    status: Mutex<Option<HubStatus>>,
+    changed: Notify,
+}
+
+impl HubMonitor {
+    pub fn new() -> Self {
+        Self {
+            status: Mutex::new(None),
+            changed: Notify::new(),
+        }
+    }
+
+    pub fn status(&self) -> Option<HubStatus> {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Asks [`watch`] to re-read the hub status now.
+    pub fn invalidate(&self) {
+        self.changed.notify_one();
+    }
+
+    /// Records `status`, notifying about agents that dropped off a hub that
+    /// is still running. Agents leaving because the hub stopped are expected.
+    fn update(&self, app: &AppHandle, status: Option<HubStatus>) {
+        let previous = {
+            let mut current = self.status.lock().unwrap();
+            if *current == status {
+                return;
+            }
+            std::mem::replace(&mut *current, status.clone())
+        };
+
+        if let (Some(previous), Some(current)) = (&previous, &status) {
+            if previous.running && current.running {
+                for agent in &previous.agents {
+                    if !current.agents.iter().any(|a| a.name == agent.name) {
+                        agent_disconnected(app, &agent.name);
+                    }
+                }
+            }
+        }
+        tray::set_hub(app, status.as_ref());
+    }
+}
+
+fn agent_disconnected(app: &AppHandle, name: &str) {
+    logs::warn(app, &format!("Hub agent {} disconnected", name));
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("Agent disconnected")
+        .body(format!("{} is no longer connected to the hub.", name))
+        .show()
+    {
+        logs::warn(app, &format!("Failed to show notification: {}", e));
+    }
+}
+
+/// Keeps the tray's hub status current. Refreshes right after the webview or
+/// tray starts or stops the hub and on a short poll otherwise.
+pub async fn watch(app: AppHandle) {
+    let monitor = app.state::<HubMonitor>();
+    let supervisor = app.state::<Supervisor>();
+    loop {
+        let status = tokio::time::timeout(
+            REQUEST_TIMEOUT,
+            proxy::get_json::<HubStatus>(supervisor.endpoint(), "/hub/status"),
+        )
+        .await;
+        monitor.update(&app, status.ok().and_then(Result::ok));
+        tokio::select! {
+            _ = sleep(POLL_INTERVAL) => {}
+            _ = monitor.changed.notified() => {}
+        }
+    }
+}
+
+/// Starts the hub if it is stopped and stops it otherwise.
+pub fn toggle(app: &AppHandle) {
+    let running = app
+        .state::<HubMonitor>()
+        .status()
+        .is_some_and(|status| status.running);
+    let path = if running { "/hub/stop" } else { "/hub/start" };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = proxy::post(app.state::<Supervisor>().endpoint(), path).await;
+        match result {
+            Ok((status, _)) if status.is_success() => {}
+            Ok((status, body)) => logs::error(
+                &app,
+                &format!(
+                    "POST {} failed: HTTP {} {}",
+                    path,
+                    status,
+                    String::from_utf8_lossy(&body)
+                ),
+            ),
+            Err(e) => logs::error(&app, &format!("POST {} failed: {}", path, e)),
+        }
+        app.state::<HubMonitor>().invalidate();
+    });
+}
@@ -0,0 +1,78 @@
+// See `Supervisor::init_script`, which replaces `__OPENASST_ENV__` with the
+// environment as a JSON object.
+(function () {
+  var env = Object.freeze(__OPENASST_ENV__);
+  window.__OPENASST__ = env;
+  var fetch = window.fetch;
+  window.fetch = function (input, init) {
+    var url = typeof input === 'string' ? input : input instanceof URL ? input.href : input.url;
+    if (url.indexOf(env.apiBaseUrl) !== 0) return fetch.call(window, input, init);
+    var request = new Request(input, init);
+    if ((request.headers.get('Accept') || '').indexOf('text/event-stream') === -1) {
+      return fetch.call(window, request);
+    }
+    return request.text().then(function (body) {
+      return new Promise(function (resolve, reject) {
+        var core = window.__TAURI__.core;
+        var controller;
+        var done = false;
+        var stream = new ReadableStream({
+          start: function (c) {
+            controller = c;
+          },
+          // The page stopped reading the body
+          cancel: function () {
+            cancel();
+          },
+        });
+        var channel = new core.Channel();
+        // Stops the request in the shell too, so the server sees the client go
+        function cancel() {
+          if (done) return;
+          done = true;
+          core.invoke('api_stream_cancel', { id: channel.id }).catch(function () {});
+        }
+        channel.onmessage = function (message) {
+          if (message instanceof ArrayBuffer) {
+            controller.enqueue(new Uint8Array(message));
+          } else if (message.type === 'head') {
+            resolve(new Response(stream, { status: message.status, headers: message.headers }));
+          } else if (message.type === 'end') {
+            done = true;
+            controller.close();
+          } else {
+            done = true;
+            controller.error(new TypeError(message.message));
+          }
+        };
+        if (request.signal) {
+          request.signal.addEventListener('abort', function () {
+            var error = new DOMException('The operation was aborted.', 'AbortError');
+            cancel();
+            try {
+              controller.error(error);
+            } catch (e) {}
+            reject(error);
+          });
+        }
+        core
+          .invoke('api_stream', {
+            request: {
+              method: request.method,
+              path: request.url.slice(env.apiBaseUrl.length) || '/',
+              headers: Array.from(request.headers.entries()),
+              body: request.method === 'GET' || request.method === 'HEAD' ? null : body,
+            },
+            channel: channel,
+          })
+          .then(function () {
+            done = true;
+          })
+          .catch(function (e) {
+            done = true;
+            reject(new TypeError(String(e)));
+          });
+      });
+    });
+  };
+})();
@@ -0,0 +1,198 @@
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_shell::ShellExt;
+use tokio::process::Command;
+
+use crate::sidecar::Supervisor;
+use crate::{logs, settings};
+
+/// Oldest Node.js major the API server runs on (see `engines` in package.json).
+const MIN_NODE_MAJOR: u32 = 18;
+
+/// SHA-256 of the bundled Node runtime, recorded by `scripts/fetch-node.mjs`
+/// and embedded by build.rs. Empty when the build bundles no runtime.
+const BUNDLED_NODE_SHA256: &str = env!("OPENASST_NODE_SHA256");
+
+/// Why the API server could not be brought up.
+#[derive(Debug)]
+pub enum LaunchError {
+    EntryMissing(PathBuf),
+    NodeNotFound,
+    NodeTooOld(String),
+    /// The bundled runtime does not match the checksum recorded at build time.
+    NodeChecksum(PathBuf),
+    Spawn(std::io::Error),
+    /// The process exited before it ever reported healthy.
+    ExitedOnStartup(Option<i32>),
+    Incompatible(String),
+    CrashLoop {
+        crashes: usize,
+        window_secs: u64,
+    },
+}
+
+impl fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaunchError::EntryMissing(path) => write!(
+                f,
+                "The bundled API server is missing ({}). Reinstall OpenAsst to restore it.",
+                path.display()
+            ),
+            LaunchError::NodeNotFound => write!(
+                f,
+                "Node.js was not found on PATH. Install Node.js {} or newer from \
+                 https://nodejs.org or your package manager, then retry.",
+                MIN_NODE_MAJOR
+            ),
+            LaunchError::NodeTooOld(version) => write!(
+                f,
+                "Node.js {} is too old. OpenAsst needs Node.js {} or newer.",
+                version, MIN_NODE_MAJOR
+            ),
+            LaunchError::NodeChecksum(path) => write!(
+                f,
+                "The bundled Node.js runtime ({}) failed its integrity check. Reinstall \
+                 OpenAsst, or enable the system Node.js runtime in settings.",
+                path.display()
+            ),
+            LaunchError::Spawn(e) => write!(f, "The API server could not be started: {}", e),
+            LaunchError::ExitedOnStartup(Some(code)) => write!(
+                f,
+                "The API server exited with code {} while starting. See the logs for details.",
+                code
+            ),
+            LaunchError::ExitedOnStartup(None) => write!(
+                f,
+                "The API server was terminated while starting. See the logs for details."
+            ),
+            LaunchError::Incompatible(reason) => f.write_str(reason),
+            LaunchError::CrashLoop {
+                crashes,
+                window_secs,
+            } => write!(
+                f,
+                "The API server crashed {} times within {}s and was not restarted again.",
+                crashes, window_secs
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LaunchError {}
+
+impl From<std::io::Error> for LaunchError {
+    fn from(e: std::io::Error) -> Self {
+        if e.kind() == ErrorKind::NotFound {
+            LaunchError::NodeNotFound
+        } else {
+            LaunchError::Spawn(e)
+        }
+    }
+}
+
+/// Checks that the API entry exists and that a recent enough Node runtime is
+/// available before the first spawn. Returns the `node` program to run.
+pub async fn preflight(app: &AppHandle, api_entry: &Path) -> Result<PathBuf, LaunchError> {
+    if !api_entry.is_file() {
+        return Err(LaunchError::EntryMissing(api_entry.to_path_buf()));
+    }
+
+    let node = resolve_node(app).await?;
+    let output = Command::new(&node).arg("--version").output().await?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let major = version
+        .trim_start_matches('v')
+        .split('.')
+        .next()
+        .and_then(|major| major.parse::<u32>().ok());
+
+    match major {
+        Some(major) if major >= MIN_NODE_MAJOR => Ok(node),
+        _ => Err(LaunchError::NodeTooOld(version)),
+    }
+}
+
+/// Picks the bundled Node runtime, unless the user prefers the system one or
+/// this build bundles none.
+async fn resolve_node(app: &AppHandle) -> Result<PathBuf, LaunchError> {
+    let system = PathBuf::from("node");
+    if settings::current(app).prefer_system_node {
+        logs::info(app, "Using the system Node.js runtime as configured");
+        return Ok(system);
+    }
+
+    let Some(bundled) = bundled_node(app) else {
+        logs::warn(app, "No bundled Node.js runtime, using the one on PATH");
+        return Ok(system);
+    };
+
+    if BUNDLED_NODE_SHA256.is_empty() {
+        // Release builds refuse to compile without a checksum, so this is a
+        // development build; anything else must not run unverified
+        if !cfg!(debug_assertions) {
+            return Err(LaunchError::NodeChecksum(bundled));
+        }
+        logs::warn(app, "No checksum recorded for the bundled Node.js runtime");
+    } else {
+        let path = bundled.clone();
+        let digest = tauri::async_runtime::spawn_blocking(move || sha256_file(&path))
+            .await
+            .map_err(|e| LaunchError::Spawn(std::io::Error::other(e)))?
+            .map_err(LaunchError::Spawn)?;
+        if !digest.eq_ignore_ascii_case(BUNDLED_NODE_SHA256) {
+            return Err(LaunchError::NodeChecksum(bundled));
+        }
+    }
+
+    logs::info(
+        app,
+        &format!("Using bundled Node.js runtime: {:?}", bundled),
+    );
+    Ok(bundled)
+}
+
+/// Path of the `node` external binary shipped next to the executable.
+fn bundled_node(app: &AppHandle) -> Option<PathBuf> {
+    let command: std::process::Command = app.shell().sidecar("node").ok()?.into();
+    let path = PathBuf::from(command.get_program());
+    path.is_file().then_some(path)
+}
+
+fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Logs the failure and shows a native error dialog offering to retry.
+/// Retrying goes through [`Supervisor::restart`], which starts supervision
+/// over only if it has not been restarted already.
+pub fn report(app: &AppHandle, error: &LaunchError) {
+    let message = error.to_string();
+    logs::error(app, &format!("API server failed to launch: {}", message));
+
+    let handle = app.clone();
+    app.dialog()
+        .message(message)
+        .title("OpenAsst cannot start its backend")
+        .kind(MessageDialogKind::Error)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Retry".to_string(),
+            "Close".to_string(),
+        ))
+        .show(move |retry| {
+            if retry {
+                handle.state::<Supervisor>().restart(&handle);
+            }
+        });
+}
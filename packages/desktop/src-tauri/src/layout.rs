@@ -0,0 +1,298 @@
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::profiles::Profiles;
+use crate::{logs, tray};
+
+/// Where the shell and the API server keep their files.
+///
+/// On Linux everything follows the XDG base directories: API data under
+/// `$XDG_CONFIG_HOME/openasst`, with the default profile in `default` and
+/// named profiles in `profiles`, caches under `$XDG_CACHE_HOME/openasst` and
+/// logs under `$XDG_STATE_HOME/openasst`. Elsewhere the API keeps its own
+/// default, `~/.openasst`, and the shell uses the platform log directory.
+#[derive(Clone, Debug)]
+pub struct Layout {
+    /// API data of the default profile; `None` leaves the API's default.
+    pub data: Option<PathBuf>,
+    /// Parent of the data directories of named profiles.
+    pub profiles: PathBuf,
+    /// API caches; `None` leaves the API's default.
+    pub cache: Option<PathBuf>,
+    /// Shell log files.
+    pub logs: PathBuf,
+    /// API log files, such as those of services it manages; `None` leaves
+    /// the API's default.
+    pub api_logs: Option<PathBuf>,
+}
+
+impl Layout {
+    #[cfg(target_os = "linux")]
+    pub fn resolve(app: &AppHandle) -> Self {
+        let path = app.path();
+        let home = path.home_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let config = path.config_dir().unwrap_or_else(|_| home.join(".config"));
+        let cache = path.cache_dir().unwrap_or_else(|_| home.join(".cache"));
+        // Tauri has no state directory; resolve it as the spec describes
+        let state = std::env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .unwrap_or_else(|| home.join(".local").join("state"));
+
+        let root = config.join(APP_DIR);
+        let logs = state.join(APP_DIR);
+        Self {
+            data: Some(root.join("default")),
+            profiles: root.join(PROFILES_DIR),
+            cache: Some(cache.join(APP_DIR)),
+            api_logs: Some(logs.join("api")),
+            logs,
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn resolve(app: &AppHandle) -> Self {
+        let path = app.path();
+        Self {
+            data: None,
+            profiles: path
+                .app_data_dir()
+                .unwrap_or_else(|_| PathBuf::from("."))
+                .join(PROFILES_DIR),
+            cache: None,
+            logs: path
+                .app_log_dir()
+                .unwrap_or_else(|_| std::env::temp_dir().join("openasst-logs")),
+            api_logs: None,
+        }
+    }
+}
+
+/// Name of the OpenAsst directory inside each XDG base directory.
+#[cfg(target_os = "linux")]
+const APP_DIR: &str = "openasst";
+
+/// Directory holding one directory per named profile.
+const PROFILES_DIR: &str = "profiles";
+
+/// Moves data left by earlier versions into the layout of the profiles:
+/// `~/.openasst`, and named profiles from the app data directory. Copying
+/// may take a while, so it runs on a blocking thread; the API server must
+/// not start before it is done. If `~/.openasst` could not be copied, it
+/// stays in place and the API keeps using it this run.
+pub async fn migrate(app: &AppHandle) {
+    let mut layout = app.state::<Profiles>().layout();
+    let handle = app.clone();
+    let migrated = tauri::async_runtime::spawn_blocking(move || {
+        move_legacy_data(&handle, &mut layout);
+        layout
+    })
+    .await;
+    match migrated {
+        Ok(layout) => {
+            app.state::<Profiles>().set_layout(layout);
+            // Shows profiles that just moved in
+            tray::set_profiles(app);
+        }
+        Err(e) => logs::error(app, &format!("Failed to move old data: {}", e)),
+    }
+}
+
+fn move_legacy_data(app: &AppHandle, layout: &mut Layout) {
+    #[cfg(target_os = "linux")]
+    if let Ok(old) = app.path().app_data_dir() {
+        let old = old.join(PROFILES_DIR);
+        match migration::move_profiles(&old, &layout.profiles) {
+            Ok(moved) if !moved.is_empty() => logs::info(
+                app,
+                &format!(
+                    "Moved profiles {} to {}",
+                    moved.join(", "),
+                    layout.profiles.display()
+                ),
+            ),
+            Ok(_) => {}
+            Err(e) => logs::error(
+                app,
+                &format!("Failed to move profiles from {}: {}", old.display(), e),
+            ),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    if let (Some(target), Ok(home)) = (layout.data.clone(), app.path().home_dir()) {
+        let legacy = home.join(".openasst");
+        match migration::run(&legacy, &target) {
+            Ok(migration::Outcome::Migrated { backup }) => logs::info(
+                app,
+                &format!(
+                    "Moved {} to {}; the original is kept at {}",
+                    legacy.display(),
+                    target.display(),
+                    backup.display()
+                ),
+            ),
+            Ok(migration::Outcome::Conflict) => logs::warn(
+                app,
+                &format!(
+                    "Both {} and {} exist; using the latter and leaving the former alone",
+                    legacy.display(),
+                    target.display()
+                ),
+            ),
+            Ok(migration::Outcome::NotNeeded) => {}
+            Err(e) => {
+                logs::error(app, &format!("Failed to move {}: {}", legacy.display(), e));
+                if !migration::is_complete(&target) {
+                    layout.data = None;
+                }
+            }
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = (app, layout);
+}
+
+#[cfg(target_os = "linux")]
+mod migration {
+    use std::fs;
+    use std::io;
+    use std::os::unix::fs::symlink;
+    use std::path::{Path, PathBuf};
+
+    use crate::profiles;
+
+    /// Written into the new directory, naming where its data came from.
+    /// Its presence also marks the copy as complete.
+    const MARKER_FILE: &str = ".migrated-from";
+
+    /// Whether `target` holds a finished copy, even if the original could not
+    /// be moved aside afterwards.
+    pub fn is_complete(target: &Path) -> bool {
+        target.join(MARKER_FILE).exists()
+    }
+
+    pub enum Outcome {
+        NotNeeded,
+        Migrated {
+            backup: PathBuf,
+        },
+        /// Both directories hold data; neither is touched.
+        Conflict,
+    }
+
+    /// Copies `legacy` into a staging directory next to `target` and renames
+    /// it into place, so `target` appears complete or not at all. The
+    /// original is then renamed to a backup, and `legacy` becomes a symlink
+    /// to `target`: an API server the shell does not start, such as one run
+    /// on its own, with `pnpm dev:api` or for `--api-url`, still defaults to
+    /// `~/.openasst` and would otherwise start with no data.
+    pub fn run(legacy: &Path, target: &Path) -> io::Result<Outcome> {
+        match fs::symlink_metadata(legacy) {
+            Ok(meta) if meta.is_dir() => {}
+            // Missing, or already the pointer left by an earlier migration
+            Ok(_) => return Ok(Outcome::NotNeeded),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Outcome::NotNeeded),
+            Err(e) => return Err(e),
+        }
+
+        if target.exists() {
+            // Copied, but interrupted before the original was moved aside
+            if is_complete(target) {
+                return retire(legacy, target);
+            }
+            return Ok(Outcome::Conflict);
+        }
+
+        let staging = staging_for(target)?;
+        copy_dir(legacy, &staging)?;
+        fs::write(staging.join(MARKER_FILE), format!("{}\n", legacy.display()))?;
+        fs::rename(&staging, target)?;
+
+        retire(legacy, target)
+    }
+
+    /// Renames `legacy` to a timestamped backup and leaves a symlink to
+    /// `target` in its place.
+    fn retire(legacy: &Path, target: &Path) -> io::Result<Outcome> {
+        let backup = legacy.with_file_name(format!(
+            ".openasst.backup-{}",
+            chrono::Local::now().format("%Y%m%d%H%M%S")
+        ));
+        fs::rename(legacy, &backup)?;
+        symlink(target, legacy)?;
+        Ok(Outcome::Migrated { backup })
+    }
+
+    /// Moves every profile directory in `from` into `to`, leaving any whose
+    /// name is already taken there, and removes `from` once it is empty.
+    /// Returns the names of the profiles moved.
+    pub fn move_profiles(from: &Path, to: &Path) -> io::Result<Vec<String>> {
+        let entries = match fs::read_dir(from) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut moved = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let Ok(name) = entry.file_name().into_string() else {
+                continue;
+            };
+            if !entry.file_type()?.is_dir() || profiles::validate_name(&name).is_err() {
+                continue;
+            }
+            let target = to.join(&name);
+            // An empty directory, as selecting the profile leaves, is not
+            // taken
+            if target.exists() && fs::remove_dir(&target).is_err() {
+                continue;
+            }
+            fs::create_dir_all(to)?;
+            // Renaming fails across file systems; copy the profile then
+            if fs::rename(entry.path(), &target).is_err() {
+                let staging = staging_for(&target)?;
+                copy_dir(&entry.path(), &staging)?;
+                fs::rename(&staging, &target)?;
+                fs::remove_dir_all(entry.path())?;
+            }
+            moved.push(name);
+        }
+        // Only succeeds once every profile has moved
+        let _ = fs::remove_dir(from);
+        Ok(moved)
+    }
+
+    /// Empty path next to `target` to copy into before renaming into place.
+    fn staging_for(target: &Path) -> io::Result<PathBuf> {
+        let (Some(parent), Some(name)) = (target.parent(), target.file_name()) else {
+            return Err(io::Error::other("target has no parent directory"));
+        };
+        fs::create_dir_all(parent)?;
+        let staging = parent.join(format!(".{}-migrating", name.to_string_lossy()));
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        Ok(staging)
+    }
+
+    /// Copies a directory tree, keeping permissions and symlinks. Sockets
+    /// and other special files are skipped.
+    fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+        fs::create_dir(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            let source = entry.path();
+            let dest = to.join(entry.file_name());
+            let kind = entry.file_type()?;
+            if kind.is_dir() {
+                copy_dir(&source, &dest)?;
+            } else if kind.is_symlink() {
+                symlink(fs::read_link(&source)?, &dest)?;
+            } else if kind.is_file() {
+                fs::copy(&source, &dest)?;
+            }
+        }
+        fs::set_permissions(to, fs::metadata(from)?.permissions())
+    }
+}
@@ -0,0 +1,191 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+const LOG_FILE: &str = "openasst.log";
+/// Size at which the current file is rotated.
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+/// Rotated files kept next to the current one (`openasst.log.1` ...).
+const MAX_ROTATED_FILES: usize = 4;
+/// Lines returned by `get_logs` when the caller does not ask for a number.
+const DEFAULT_TAIL_LINES: usize = 200;
+/// Most lines `get_logs` returns, however many are asked for.
+const MAX_TAIL_LINES: usize = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Level {
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        }
+    }
+}
+
+/// Where a log line came from.
+#[derive(Clone, Copy, Debug)]
+pub enum Source {
+    Shell,
+    Api,
+}
+
+impl Source {
+    fn as_str(self) -> &'static str {
+        match self {
+            Source::Shell => "shell",
+            Source::Api => "api",
+        }
+    }
+}
+
+struct OpenFile {
+    file: File,
+    size: u64,
+}
+
+/// Managed state writing timestamped log lines to size-rotated files in the
+/// platform log directory.
+pub struct Logger {
+    dir: PathBuf,
+    /// Lines below this level are dropped.
+    level: Level,
+    file: Mutex<Option<OpenFile>>,
+}
+
+impl Logger {
+    pub fn new(dir: PathBuf, level: Level) -> Self {
+        Self {
+            dir,
+            level,
+            file: Mutex::new(None),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, index: usize) -> PathBuf {
+        if index == 0 {
+            self.dir.join(LOG_FILE)
+        } else {
+            self.dir.join(format!("{}.{}", LOG_FILE, index))
+        }
+    }
+
+    /// Writes `message` to the log file and echoes it to stdout/stderr.
+    pub fn write(&self, level: Level, source: Source, message: &str) {
+        if level < self.level {
+            return;
+        }
+        let line = format!(
+            "{} {:<5} [{}] {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            level.as_str(),
+            source.as_str(),
+            message
+        );
+
+        match level {
+            Level::Info => print!("{}", line),
+            Level::Warn | Level::Error => eprint!("{}", line),
+        }
+
+        let mut guard = self.file.lock().unwrap();
+        if guard
+            .as_ref()
+            .is_some_and(|f| f.size + line.len() as u64 > MAX_FILE_SIZE)
+        {
+            *guard = None;
+            self.rotate();
+        }
+        if guard.is_none() {
+            *guard = self.open().ok();
+        }
+        if let Some(open) = guard.as_mut() {
+            if open.file.write_all(line.as_bytes()).is_ok() {
+                open.size += line.len() as u64;
+            }
+        }
+    }
+
+    fn open(&self) -> std::io::Result<OpenFile> {
+        fs::create_dir_all(&self.dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(0))?;
+        let size = file.metadata()?.len();
+        Ok(OpenFile { file, size })
+    }
+
+    fn rotate(&self) {
+        let _ = fs::remove_file(self.path(MAX_ROTATED_FILES));
+        for index in (0..MAX_ROTATED_FILES).rev() {
+            let _ = fs::rename(self.path(index), self.path(index + 1));
+        }
+    }
+
+    /// Returns up to the last `lines` lines, oldest first, reaching into
+    /// rotated files when the current one is shorter.
+    pub fn tail(&self, lines: usize) -> Vec<String> {
+        let mut tail: Vec<String> = Vec::new();
+        for index in 0..=MAX_ROTATED_FILES {
+            if tail.len() >= lines {
+                break;
+            }
+            let Ok(contents) = fs::read_to_string(self.path(index)) else {
+                break;
+            };
+            let wanted = lines - tail.len();
+            let mut older: Vec<String> = contents
+                .lines()
+                .rev()
+                .take(wanted)
+                .map(str::to_string)
+                .collect();
+            older.reverse();
+            older.append(&mut tail);
+            tail = older;
+        }
+        tail
+    }
+}
+
+/// Logs through the managed [`Logger`], or straight to stdout/stderr when it
+/// has not been set up yet.
+pub fn log(app: &AppHandle, level: Level, source: Source, message: &str) {
+    match app.try_state::<Logger>() {
+        Some(logger) => logger.write(level, source, message),
+        None => match level {
+            Level::Info => println!("{}", message),
+            Level::Warn | Level::Error => eprintln!("{}", message),
+        },
+    }
+}
+
+pub fn info(app: &AppHandle, message: &str) {
+    log(app, Level::Info, Source::Shell, message);
+}
+
+pub fn warn(app: &AppHandle, message: &str) {
+    log(app, Level::Warn, Source::Shell, message);
+}
+
+pub fn error(app: &AppHandle, message: &str) {
+    log(app, Level::Error, Source::Shell, message);
+}
+
+#[tauri::command]
+pub fn get_logs(logger: tauri::State<'_, Logger>, lines: Option<usize>) -> Vec<String> {
+    logger.tail(lines.unwrap_or(DEFAULT_TAIL_LINES).min(MAX_TAIL_LINES))
+}
@@ -1,38 +1,221 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod cli;
+mod close;
+mod deep_link;
+mod devices;
+mod health;
+mod hub;
+mod launch;
+mod layout;
+mod logs;
+mod previews;
+mod profiles;
+mod proxy;
+mod quick_prompt;
+mod robots;
+mod settings;
 mod sidecar;
 mod tray;
+mod windows;
 
-#[allow(unused_imports)]
-use tauri::Manager;
+use clap::Parser;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_window_state::StateFlags;
+
+/// Event carrying the arguments of a second launch to the running instance.
+const SECOND_INSTANCE_EVENT: &str = "app://second-instance";
+
+#[derive(Clone, Serialize)]
+struct SecondInstance {
+    args: Vec<String>,
+    cwd: String,
+}
+
+/// Called in the running instance when OpenAsst is launched again: switches
+/// to a profile given with `--profile`, focuses the window, opens a link
+/// given with `--open` and forwards the new launch's arguments, minus the
+/// program.
+fn on_second_instance(app: &AppHandle, argv: Vec<String>, cwd: String) {
+    let cli = cli::Cli::try_parse_from(&argv).ok();
+    let args: Vec<String> = argv.into_iter().skip(1).collect();
+    logs::info(app, &format!("Second launch forwarded: {:?}", args));
+    if let Some(name) = cli.as_ref().and_then(|cli| cli.profile.as_deref()) {
+        if let Err(e) = profiles::switch(app, name) {
+            logs::warn(app, &format!("Ignoring --profile {}: {}", name, e));
+            let active = app.state::<profiles::Profiles>().active();
+            notify(app, &format!("Still using profile {}", active), &e);
+        }
+    }
+    match cli.and_then(|cli| cli.open) {
+        Some(url) => deep_link::open_url(app, &url),
+        None => tray::show_main_window(app),
+    }
+    let _ = app.emit(SECOND_INSTANCE_EVENT, SecondInstance { args, cwd });
+}
+
+fn notify(app: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        logs::warn(app, &format!("Failed to show notification: {}", e));
+    }
+}
 
 fn main() {
-    tauri::Builder::default()
+    // Release builds on Windows start without a console, so clap's output
+    // would go nowhere; use the one of the terminal we were started from
+    #[cfg(all(windows, not(debug_assertions)))]
+    unsafe {
+        use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+    // Exits on --help, --version and invalid arguments
+    let cli = cli::Cli::parse();
+
+    let env_script = sidecar::Supervisor::init_script();
+
+    let mut builder = tauri::Builder::default()
+        // Must come first so a second launch exits before starting anything
+        .plugin(tauri_plugin_single_instance::init(on_second_instance))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build());
+    // Remember each window's geometry by label. Positions on monitors that
+    // are gone fall back to the window's default placement. Visibility is
+    // left to the app, and the quick prompt centres itself when shown.
+    if !cli.safe_mode {
+        builder = builder.plugin(
+            tauri_plugin_window_state::Builder::new()
+                .with_state_flags(
+                    StateFlags::SIZE
+                        | StateFlags::POSITION
+                        | StateFlags::MAXIMIZED
+                        | StateFlags::FULLSCREEN,
+                )
+                .with_denylist(&[quick_prompt::WINDOW_LABEL])
+                .build(),
+        );
+    }
+
+    builder
+        .plugin(
+            tauri::plugin::Builder::<tauri::Wry>::new("openasst-env")
+                .js_init_script(env_script)
+                .build(),
+        )
+        .register_asynchronous_uri_scheme_protocol(proxy::SCHEME, |ctx, request, responder| {
+            let app = ctx.app_handle().clone();
+            let label = ctx.webview_label().to_string();
+            tauri::async_runtime::spawn(async move {
+                responder.respond(proxy::handle(&app, &label, request).await);
+            });
+        })
+        .manage(cli)
+        .manage(health::HealthMonitor::new())
+        .manage(devices::DeviceMonitor::new())
+        .manage(hub::HubMonitor::new())
+        .manage(robots::RobotMonitor::new())
+        .manage(previews::PreviewMonitor::new())
+        .manage(proxy::Streams::default())
+        .invoke_handler(tauri::generate_handler![
+            sidecar::get_sidecar_state,
+            sidecar::get_api_base_url,
+            proxy::api_stream,
+            proxy::api_stream_cancel,
+            health::get_api_health,
+            logs::get_logs,
+            deep_link::take_pending_deep_link,
+            quick_prompt::hide_quick_prompt,
+            quick_prompt::open_session_in_main_window,
+            profiles::get_profiles,
+            profiles::switch_profile,
+            settings::get_settings,
+            settings::set_settings
+        ])
         .setup(|app| {
-            // Start the API server sidecar
+            // Write shell and sidecar logs to the log directory of the layout
+            let layout = layout::Layout::resolve(app.handle());
+            let cli = app.state::<cli::Cli>();
+            app.manage(logs::Logger::new(layout.logs.clone(), cli.log_level));
+
+            if cli.safe_mode {
+                logs::info(app.handle(), "Safe mode: using default settings");
+                app.manage(settings::SettingsStore::in_memory());
+            } else {
+                let config_dir = app
+                    .path()
+                    .app_config_dir()
+                    .unwrap_or_else(|_| std::path::PathBuf::from("."));
+                app.manage(settings::SettingsStore::load(config_dir));
+            }
+
+            profiles::setup(app.handle(), layout);
+
+            // Needs the settings, and must exist before any window loads
+            let remote = settings::current(app.handle())
+                .backend
+                .remote_endpoint()
+                .unwrap_or_else(|e| {
+                    logs::error(app.handle(), &format!("Ignoring the remote backend: {}", e));
+                    None
+                });
+            let endpoint = proxy::Endpoint::for_launch(&cli, remote);
+            let mut config = sidecar::SupervisorConfig::from_env();
+            config.external |= cli.api_url.is_some() || endpoint.is_remote();
+            app.manage(sidecar::Supervisor::new(config, endpoint));
+
+            windows::setup(app.handle())?;
+            deep_link::setup(app.handle());
+            quick_prompt::setup(app.handle());
+
+            // Start the API server sidecar once old data has moved into place
             let handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
+                layout::migrate(&handle).await;
                 if let Err(e) = sidecar::start_api_server(&handle).await {
-                    eprintln!("Failed to start API server: {}", e);
+                    logs::error(&handle, &format!("Failed to start API server: {}", e));
                 }
             });
 
             // Setup tray
             tray::setup_tray(app)?;
+            tauri::async_runtime::spawn(devices::watch(app.handle().clone()));
+            tauri::async_runtime::spawn(hub::watch(app.handle().clone()));
+            tauri::async_runtime::spawn(robots::watch(app.handle().clone()));
+            tauri::async_runtime::spawn(previews::watch(app.handle().clone()));
 
             Ok(())
         })
         .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                // Hide window instead of closing on macOS
-                #[cfg(target_os = "macos")]
-                {
+            // The quick prompt behaves like a popover and goes away on blur
+            if window.label() == quick_prompt::WINDOW_LABEL {
+                if let tauri::WindowEvent::Focused(false) = event {
                     window.hide().unwrap_or_default();
-                    api.prevent_close();
                 }
+                return;
+            }
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                close::on_close_requested(window, api);
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            // Headless, closing the last window leaves the tray running
+            tauri::RunEvent::ExitRequested {
+                code: None, api, ..
+            } if windows::is_headless(app) => api.prevent_exit(),
+            tauri::RunEvent::Exit => {
+                // Absent if setup failed before choosing the backend
+                if let Some(supervisor) = app.try_state::<sidecar::Supervisor>() {
+                    supervisor.shutdown(app);
+                }
+            }
+            _ => {}
+        });
 }
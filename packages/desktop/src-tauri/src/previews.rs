@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+use tokio::sync::Notify;
+use tokio::time::{sleep, Duration};
+
+use crate::logs;
+use crate::proxy;
+use crate::sidecar::Supervisor;
+use crate::tray;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A running Vite preview server as listed by `GET /preview/status`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Preview {
+    pub task_id: String,
+    pub port: u16,
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+struct PreviewsResponse {
+    previews: Vec<Preview>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StopRequest<'a> {
+    task_id: &'a str,
+}
+
+/// Managed state holding the previews last shown in the tray.
+pub struct PreviewMonitor {
+    previews: Mutex<Option<Vec<Preview>>>,
+    changed: Notify,
+}
+
+impl PreviewMonitor {
+    pub fn new() -> Self {
+        Self {
+            previews: Mutex::new(None),
+            changed: Notify::new(),
+        }
+    }
+
+    /// Asks [`watch`] to re-read the previews now.
+    pub fn invalidate(&self) {
+        self.changed.notify_one();
+    }
+
+    fn preview(&self, task_id: &str) -> Option<Preview> {
+        self.previews
+            .lock()
+            .unwrap()
+            .as_ref()?
+            .iter()
+            .find(|preview| preview.task_id == task_id)
+            .cloned()
+    }
+}
+
+/// Keeps the tray's preview submenu in sync with the API. Refreshes after
+/// previews are started or stopped through the shell and on a poll otherwise.
+/// An unreachable API has no previews.
+pub async fn watch(app: AppHandle) {
+    let monitor = app.state::<PreviewMonitor>();
+    let supervisor = app.state::<Supervisor>();
+    loop {
+        let previews = tokio::time::timeout(
+            REQUEST_TIMEOUT,
+            proxy::get_json::<PreviewsResponse>(supervisor.endpoint(), "/preview/status"),
+        )
+        .await
+        .ok()
+        .and_then(Result::ok)
+        .map(|response| response.previews)
+        .unwrap_or_default();
+
+        let changed = {
+            let mut current = monitor.previews.lock().unwrap();
+            let changed = current.as_ref() != Some(&previews);
+            *current = Some(previews.clone());
+            changed
+        };
+        if changed {
+            tray::set_previews(&app, &previews);
+        }
+        tokio::select! {
+            _ = sleep(POLL_INTERVAL) => {}
+            _ = monitor.changed.notified() => {}
+        }
+    }
+}
+
+/// Opens the preview in the default browser.
+pub fn open(app: &AppHandle, task_id: &str) {
+    let Some(preview) = app.state::<PreviewMonitor>().preview(task_id) else {
+        return;
+    };
+    if let Err(e) = app.opener().open_url(&preview.url, None::<&str>) {
+        logs::error(app, &format!("Failed to open {}: {}", preview.url, e));
+    }
+}
+
+/// Stops one preview server.
+pub fn stop(app: &AppHandle, task_id: &str) {
+    let app = app.clone();
+    let task_id = task_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let request = StopRequest { task_id: &task_id };
+        let result = proxy::post_json(
+            app.state::<Supervisor>().endpoint(),
+            "/preview/stop",
+            &request,
+        )
+        .await;
+        report(&app, "/preview/stop", result);
+        app.state::<PreviewMonitor>().invalidate();
+    });
+}
+
+/// Stops every preview server.
+pub fn stop_all(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = proxy::post(app.state::<Supervisor>().endpoint(), "/preview/stop-all").await;
+        report(&app, "/preview/stop-all", result);
+        app.state::<PreviewMonitor>().invalidate();
+    });
+}
+
+/// Asks the API to stop its previews before it is terminated. The process
+/// group kill covers them on Unix, but on Windows nothing else stops the
+/// Vite servers, which would outlive the app. Blocks for at most
+/// [`REQUEST_TIMEOUT`].
+pub fn stop_all_before_exit(app: &AppHandle, supervisor: &Supervisor) {
+    let result = tauri::async_runtime::block_on(tokio::time::timeout(
+        REQUEST_TIMEOUT,
+        proxy::post(supervisor.endpoint(), "/preview/stop-all"),
+    ))
+    .unwrap_or_else(|_| Err("request timed out".to_string()));
+    report(app, "/preview/stop-all", result);
+}
+
+fn report(
+    app: &AppHandle,
+    path: &str,
+    result: Result<(tauri::http::StatusCode, bytes::Bytes), String>,
+) {
+    match result {
+        Ok((status, _)) if status.is_success() => {}
+        Ok((status, _)) => logs::warn(app, &format!("POST {} failed: HTTP {}", path, status)),
+        Err(e) => logs::warn(app, &format!("POST {} failed: {}", path, e)),
+    }
+}
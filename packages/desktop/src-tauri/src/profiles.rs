@@ -0,0 +1,288 @@
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio::time::{sleep, Duration, Instant};
+
+use crate::cli::Cli;
+use crate::devices::DeviceMonitor;
+use crate::hub::HubMonitor;
+use crate::layout::Layout;
+use crate::previews::PreviewMonitor;
+use crate::robots::RobotMonitor;
+use crate::settings::{self, SettingsStore};
+use crate::sidecar::{SidecarState, Supervisor};
+use crate::{logs, tray, windows};
+
+/// Profile whose data stays in the default location of the layout.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Directory under the cache and log directories holding one directory per
+/// named profile.
+const PROFILES_DIR: &str = "profiles";
+
+/// Directory in a named profile's data holding what the default profile
+/// keeps in `~/openasst`.
+const WORKSPACE_DIR: &str = "workspace";
+
+const MAX_NAME_LEN: usize = 64;
+
+/// How long a switch waits for the API server to come back before reloading
+/// the windows anyway.
+const SWITCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Checks that `name` can be used as a directory name on every platform.
+pub fn validate_name(name: &str) -> Result<String, String> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    if valid {
+        Ok(name.to_string())
+    } else {
+        Err(format!(
+            "invalid profile name {:?}: use up to {} letters, digits, '-' or '_'",
+            name, MAX_NAME_LEN
+        ))
+    }
+}
+
+/// Managed state holding the active profile and where profile data lives.
+pub struct Profiles {
+    layout: Mutex<Layout>,
+    active: Mutex<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileList {
+    pub active: String,
+    pub profiles: Vec<String>,
+}
+
+impl Profiles {
+    pub fn active(&self) -> String {
+        self.active.lock().unwrap().clone()
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout.lock().unwrap().clone()
+    }
+
+    /// Replaces the layout once the migration has settled where data lives.
+    pub fn set_layout(&self, layout: Layout) {
+        *self.layout.lock().unwrap() = layout;
+    }
+
+    /// Directories passed to the API server for the active profile. Those
+    /// the layout leaves to the API's defaults are omitted.
+    pub fn env(&self) -> Vec<(&'static str, PathBuf)> {
+        let layout = self.layout();
+        let active = self.active();
+        let (data, workspace, cache, logs) = if active == DEFAULT_PROFILE {
+            (
+                layout.data.clone(),
+                None,
+                layout.cache.clone(),
+                layout.api_logs.clone(),
+            )
+        } else {
+            let scoped = |dir: &PathBuf| dir.join(PROFILES_DIR).join(&active);
+            let data = layout.profiles.join(&active);
+            (
+                Some(data.clone()),
+                // Named profiles keep their robots and knowledge base with
+                // their data rather than in the shared ~/openasst
+                Some(data.join(WORKSPACE_DIR)),
+                layout.cache.as_ref().map(scoped),
+                layout.api_logs.as_ref().map(scoped),
+            )
+        };
+        [
+            ("OPENASST_DATA_DIR", data),
+            ("OPENASST_WORKSPACE_DIR", workspace),
+            ("OPENASST_CACHE_DIR", cache),
+            ("OPENASST_LOG_DIR", logs),
+        ]
+        .into_iter()
+        .filter_map(|(name, dir)| Some((name, dir?)))
+        .collect()
+    }
+
+    /// The default profile, then every profile directory by name.
+    pub fn list(&self) -> ProfileList {
+        let mut profiles: Vec<String> = fs::read_dir(self.layout().profiles)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .filter(|entry| entry.path().is_dir())
+                    .filter_map(|entry| entry.file_name().into_string().ok())
+                    .filter(|name| name != DEFAULT_PROFILE && validate_name(name).is_ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        profiles.sort();
+        profiles.insert(0, DEFAULT_PROFILE.to_string());
+        ProfileList {
+            active: self.active(),
+            profiles,
+        }
+    }
+
+    fn activate(&self, name: &str) -> std::io::Result<()> {
+        if name != DEFAULT_PROFILE {
+            fs::create_dir_all(self.layout().profiles.join(name))?;
+        }
+        *self.active.lock().unwrap() = name.to_string();
+        Ok(())
+    }
+}
+
+/// Picks the profile given with `--profile`, else the one last used.
+pub fn setup(app: &AppHandle, layout: Layout) {
+    let name = app
+        .state::<Cli>()
+        .profile
+        .clone()
+        .or_else(|| validate_name(&settings::current(app).profile).ok())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+    let profiles = Profiles {
+        layout: Mutex::new(layout),
+        active: Mutex::new(DEFAULT_PROFILE.to_string()),
+    };
+    if let Err(e) = profiles.activate(&name) {
+        logs::error(
+            app,
+            &format!("Cannot use profile {}, using the default: {}", name, e),
+        );
+    }
+    logs::info(app, &format!("Profile: {}", profiles.active()));
+    app.manage(profiles);
+}
+
+/// Title of the main window, naming the profile unless it is the default.
+pub fn window_title(app: &AppHandle) -> String {
+    let active = app
+        .try_state::<Profiles>()
+        .map(|profiles| profiles.active())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+    if active == DEFAULT_PROFILE {
+        "OpenAsst".to_string()
+    } else {
+        format!("OpenAsst — {}", active)
+    }
+}
+
+/// Makes `name` the active profile, creating it if needed, and restarts the
+/// API server on its data. The windows reload once the server is back.
+pub fn switch(app: &AppHandle, name: &str) -> Result<(), String> {
+    let name = validate_name(name)?;
+    let profiles = app.state::<Profiles>();
+    if profiles.active() == name {
+        // The clicked check item toggled itself off; put the mark back
+        tray::set_profiles(app);
+        return Ok(());
+    }
+    let supervisor = app.state::<Supervisor>();
+    if matches!(supervisor.state(), SidecarState::External) {
+        tray::set_profiles(app);
+        return Err("Profiles apply to the bundled API server only".to_string());
+    }
+
+    profiles.activate(&name).map_err(|e| e.to_string())?;
+    let store = app.state::<SettingsStore>();
+    let mut settings = store.get();
+    settings.profile = name.clone();
+    if let Err(e) = store.set(settings) {
+        logs::warn(app, &format!("Failed to save settings: {}", e));
+    }
+
+    logs::info(app, &format!("Switching to profile {}", name));
+    if let Some(window) = app.get_webview_window(windows::MAIN_WINDOW_LABEL) {
+        window.set_title(&window_title(app)).unwrap_or_default();
+    }
+    tray::set_profiles(app);
+
+    let previous_pid = supervisor.pid();
+    supervisor.restart(app);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        wait_for_new_server(&app, previous_pid).await;
+        reload(&app);
+    });
+    Ok(())
+}
+
+/// Waits until a server other than `previous_pid` is running.
+async fn wait_for_new_server(app: &AppHandle, previous_pid: Option<u32>) {
+    let supervisor = app.state::<Supervisor>();
+    let deadline = Instant::now() + SWITCH_TIMEOUT;
+    while Instant::now() < deadline {
+        match supervisor.state() {
+            SidecarState::Running { pid } if pid != previous_pid => return,
+            SidecarState::Failed { .. } | SidecarState::Stopped => return,
+            _ => sleep(Duration::from_millis(250)).await,
+        }
+    }
+}
+
+/// Points everything that shows API data at the new profile's server.
+fn reload(app: &AppHandle) {
+    app.state::<DeviceMonitor>().invalidate();
+    app.state::<HubMonitor>().invalidate();
+    app.state::<RobotMonitor>().invalidate();
+    app.state::<PreviewMonitor>().invalidate();
+    for window in app.webview_windows().values() {
+        window.reload().unwrap_or_default();
+    }
+}
+
+#[tauri::command]
+pub fn get_profiles(profiles: tauri::State<'_, Profiles>) -> ProfileList {
+    profiles.list()
+}
+
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, name: String) -> Result<(), String> {
+    switch(&app, &name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_plain_names() {
+        for name in ["work", "Work-2", "a_b", "0"] {
+            assert_eq!(validate_name(name), Ok(name.to_string()));
+        }
+        assert!(validate_name(&"a".repeat(MAX_NAME_LEN)).is_ok());
+    }
+
+    #[test]
+    fn rejects_path_traversal() {
+        for name in [
+            ".",
+            "..",
+            "../default",
+            "..\\default",
+            "a/b",
+            "/etc",
+            "C:\\Users",
+            ".hidden",
+        ] {
+            assert!(validate_name(name).is_err(), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn rejects_unusual_names() {
+        for name in ["", " ", "a b", "a\0b", "a\nb", "é", "a:b", "a*"] {
+            assert!(validate_name(name).is_err(), "{:?}", name);
+        }
+        assert!(validate_name(&"a".repeat(MAX_NAME_LEN + 1)).is_err());
+    }
+}
@@ -0,0 +1,617 @@
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::client::conn::http1;
+use hyper_util::rt::TokioIo;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::http::{header, HeaderValue, Method, Request, Response, StatusCode};
+use tauri::ipc::{Channel, InvokeResponseBody};
+use tauri::{AppHandle, Manager, Url};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::Notify;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+use crate::cli::Cli;
+use crate::devices::DeviceMonitor;
+use crate::hub::HubMonitor;
+use crate::previews::PreviewMonitor;
+use crate::robots::RobotMonitor;
+use crate::sidecar::{self, Supervisor, TOKEN_HEADER};
+use crate::{logs, quick_prompt, windows};
+
+/// URI scheme the webview uses to reach the API through the shell.
+pub const SCHEME: &str = "api";
+
+/// Port of the separately started API server in development (`pnpm dev:api`).
+const DEV_API_PORT: u16 = 2026;
+
+/// Host the shell connects to for servers given only by port.
+const LOOPBACK: &str = "127.0.0.1";
+
+/// Which API server the shell uses, as chosen in the settings.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum BackendTarget {
+    /// The bundled API server, started and supervised by the shell.
+    #[default]
+    Local,
+    /// A shared API server, e.g. one deployed from the repo's Dockerfile.
+    /// `token` is the `OPENASST_API_TOKEN` it was started with, if any.
+    Remote { url: String, token: String },
+}
+
+impl BackendTarget {
+    /// The endpoint of a remote target, or `None` for the local sidecar.
+    pub fn remote_endpoint(&self) -> Result<Option<Endpoint>, String> {
+        match self {
+            BackendTarget::Local => Ok(None),
+            BackendTarget::Remote { url, token } => Endpoint::remote(url, token).map(Some),
+        }
+    }
+}
+
+/// Where the shell reaches the API server.
+#[derive(Clone, Debug)]
+pub enum Endpoint {
+    /// Loopback TCP address; used for the dev server started with
+    /// `pnpm dev:api` and for `--api-url` and `--port`. IPv6 hosts keep
+    /// their brackets, as in a URL.
+    Tcp {
+        host: String,
+        port: u16,
+        token: String,
+    },
+    /// Unix domain socket, or named pipe on Windows, private to this launch.
+    Socket { path: PathBuf, token: String },
+    /// API server on another machine, reached over HTTP(S). The webview still
+    /// only talks to the `api:` scheme, so neither the CSP nor the page ever
+    /// sees the remote origin or its token.
+    Remote { url: Url, token: String },
+}
+
+impl Endpoint {
+    /// Validates a remote server's base URL. A path is kept as a prefix for
+    /// servers behind a reverse proxy.
+    pub fn remote(url: &str, token: &str) -> Result<Self, String> {
+        let url = Url::parse(url.trim()).map_err(|e| format!("invalid URL: {}", e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err("the URL must start with http:// or https://".to_string());
+        }
+        if url.host_str().is_none() {
+            return Err("the URL has no host".to_string());
+        }
+        if url.query().is_some() || url.fragment().is_some() || !url.username().is_empty() {
+            return Err("the URL must not contain credentials, a query or a fragment".to_string());
+        }
+        Ok(Endpoint::Remote {
+            url,
+            token: token.trim().to_string(),
+        })
+    }
+
+    /// The server at `--api-url` or on `--port`, else the `remote` backend
+    /// from the settings, else the dev server's fixed port in debug builds,
+    /// otherwise a socket that only this launch knows about.
+    pub fn for_launch(cli: &Cli, remote: Option<Endpoint>) -> Self {
+        if let Some(url) = &cli.api_url {
+            return Endpoint::Tcp {
+                host: url.host_str().unwrap_or(LOOPBACK).to_string(),
+                port: url.port_or_known_default().unwrap_or(80),
+                token: sidecar::generate_token(),
+            };
+        }
+        if let Some(port) = cli.port {
+            return Endpoint::loopback(port);
+        }
+        if let Some(remote) = remote {
+            return remote;
+        }
+        if cfg!(debug_assertions) {
+            return Endpoint::loopback(DEV_API_PORT);
+        }
+        let name = format!("openasst-api-{}", std::process::id());
+        #[cfg(windows)]
+        let path = PathBuf::from(format!(r"\\.\pipe\{}", name));
+        #[cfg(not(windows))]
+        let path = socket_dir().join(format!("{}.sock", name));
+        Endpoint::Socket {
+            path,
+            token: sidecar::generate_token(),
+        }
+    }
+
+    fn loopback(port: u16) -> Self {
+        Endpoint::Tcp {
+            host: LOOPBACK.to_string(),
+            port,
+            token: sidecar::generate_token(),
+        }
+    }
+
+    /// Short description for logs and diagnostics.
+    pub fn describe(&self) -> String {
+        match self {
+            Endpoint::Tcp { host, port, .. } => format!("{}:{}", host, port),
+            Endpoint::Socket { path, .. } => format!("socket {}", path.display()),
+            Endpoint::Remote { url, .. } => url.to_string(),
+        }
+    }
+
+    /// Secret sent with every request: the one the sidecar is started with,
+    /// or the remote server's, which may be empty.
+    pub fn token(&self) -> &str {
+        match self {
+            Endpoint::Tcp { token, .. }
+            | Endpoint::Socket { token, .. }
+            | Endpoint::Remote { token, .. } => token,
+        }
+    }
+
+    pub fn is_remote(&self) -> bool {
+        matches!(self, Endpoint::Remote { .. })
+    }
+
+    /// Value of the `Host` header sent to the server.
+    fn host(&self) -> String {
+        match self {
+            Endpoint::Tcp { host, port, .. } => format!("{}:{}", host, port),
+            Endpoint::Remote { url, .. } => match (url.host_str(), url.port()) {
+                (Some(host), Some(port)) => format!("{}:{}", host, port),
+                (host, _) => host.unwrap_or_default().to_string(),
+            },
+            _ => "localhost".to_string(),
+        }
+    }
+
+    /// Path prepended to every request.
+    fn base_path(&self) -> &str {
+        match self {
+            Endpoint::Remote { url, .. } => url.path().trim_end_matches('/'),
+            _ => "",
+        }
+    }
+}
+
+/// Directory for the launch's socket that only the current user can enter:
+/// `$XDG_RUNTIME_DIR`, else `openasst-<uid>` in the temporary directory.
+/// Should another user own that one, a new directory with a random name is
+/// used instead.
+#[cfg(unix)]
+fn socket_dir() -> PathBuf {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+    if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute() && dir.is_dir())
+    {
+        return dir;
+    }
+
+    let uid = unsafe { libc::getuid() };
+    let mut builder = std::fs::DirBuilder::new();
+    builder.mode(0o700);
+    let shared = std::env::temp_dir().join(format!("openasst-{}", uid));
+    let _ = builder.create(&shared);
+    let private = std::fs::symlink_metadata(&shared)
+        .is_ok_and(|meta| meta.is_dir() && meta.uid() == uid)
+        && std::fs::set_permissions(&shared, std::fs::Permissions::from_mode(0o700)).is_ok();
+    if private {
+        return shared;
+    }
+    let token = sidecar::generate_token();
+    let fresh = std::env::temp_dir().join(format!("openasst-{}-{}", uid, &token[..16]));
+    let _ = builder.create(&fresh);
+    fresh
+}
+
+/// TLS settings for remote servers, trusting the Mozilla root certificates.
+fn tls_connector() -> TlsConnector {
+    static CONFIG: OnceLock<Arc<ClientConfig>> = OnceLock::new();
+    let config = CONFIG.get_or_init(|| {
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        Arc::new(
+            ClientConfig::builder()
+                .with_root_certificates(roots)
+                .with_no_client_auth(),
+        )
+    });
+    TlsConnector::from(config.clone())
+}
+
+/// Base URL of the API as seen from the webview. Custom schemes are served
+/// from `http://<scheme>.localhost` on Windows.
+pub fn webview_base_url() -> String {
+    if cfg!(windows) {
+        format!("http://{}.localhost", SCHEME)
+    } else {
+        format!("{}://localhost", SCHEME)
+    }
+}
+
+async fn handshake<S>(stream: S) -> Result<http1::SendRequest<Full<Bytes>>, String>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (sender, connection) = http1::handshake(TokioIo::new(stream))
+        .await
+        .map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn(async move {
+        let _ = connection.await;
+    });
+    Ok(sender)
+}
+
+async fn connect(endpoint: &Endpoint) -> Result<http1::SendRequest<Full<Bytes>>, String> {
+    let io_err = |e: std::io::Error| format!("cannot reach API server: {}", e);
+    match endpoint {
+        Endpoint::Tcp { host, port, .. } => {
+            let stream = tokio::net::TcpStream::connect((host.trim_matches(['[', ']']), *port))
+                .await
+                .map_err(io_err)?;
+            handshake(stream).await
+        }
+        #[cfg(unix)]
+        Endpoint::Socket { path, .. } => {
+            let stream = tokio::net::UnixStream::connect(path)
+                .await
+                .map_err(io_err)?;
+            handshake(stream).await
+        }
+        #[cfg(windows)]
+        Endpoint::Socket { path, .. } => {
+            let pipe = tokio::net::windows::named_pipe::ClientOptions::new()
+                .open(path)
+                .map_err(io_err)?;
+            handshake(pipe).await
+        }
+        Endpoint::Remote { url, .. } => {
+            // IPv6 hosts keep their brackets in the URL
+            let host = url.host_str().unwrap_or_default().trim_matches(['[', ']']);
+            let port = url.port_or_known_default().unwrap_or(80);
+            let stream = tokio::net::TcpStream::connect((host, port))
+                .await
+                .map_err(io_err)?;
+            if url.scheme() != "https" {
+                return handshake(stream).await;
+            }
+            let name = ServerName::try_from(host.to_string()).map_err(|e| e.to_string())?;
+            let stream = tls_connector()
+                .connect(name, stream)
+                .await
+                .map_err(io_err)?;
+            handshake(stream).await
+        }
+    }
+}
+
+/// Sends `request` to the API server at `endpoint`, adding its token. Only
+/// the path and query of the request URI are used.
+pub async fn send(
+    endpoint: &Endpoint,
+    mut request: Request<Full<Bytes>>,
+) -> Result<Response<Incoming>, String> {
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "/".to_string());
+    *request.uri_mut() = format!("{}{}", endpoint.base_path(), path)
+        .parse()
+        .map_err(|e| format!("invalid path: {}", e))?;
+
+    let token = endpoint.token();
+    let headers = request.headers_mut();
+    if let Ok(host) = HeaderValue::from_str(&endpoint.host()) {
+        headers.insert(header::HOST, host);
+    }
+    if !token.is_empty() {
+        if let Ok(token) = HeaderValue::from_str(token) {
+            headers.insert(TOKEN_HEADER, token);
+        }
+    }
+
+    let mut sender = connect(endpoint).await?;
+    sender
+        .send_request(request)
+        .await
+        .map_err(|e| format!("API request failed: {}", e))
+}
+
+/// Sends a bodiless GET to the API server and returns the status and body.
+pub async fn get(endpoint: &Endpoint, path: &str) -> Result<(StatusCode, Bytes), String> {
+    request(endpoint, Method::GET, path, None).await
+}
+
+/// Sends a bodiless POST to the API server and returns the status and body.
+pub async fn post(endpoint: &Endpoint, path: &str) -> Result<(StatusCode, Bytes), String> {
+    request(endpoint, Method::POST, path, None).await
+}
+
+/// POSTs `body` as JSON to the API server and returns the status and body.
+pub async fn post_json<B: Serialize>(
+    endpoint: &Endpoint,
+    path: &str,
+    body: &B,
+) -> Result<(StatusCode, Bytes), String> {
+    let body = serde_json::to_vec(body).map_err(|e| e.to_string())?;
+    request(endpoint, Method::POST, path, Some(body)).await
+}
+
+/// GETs `path` and parses a successful response as JSON.
+pub async fn get_json<T: DeserializeOwned>(endpoint: &Endpoint, path: &str) -> Result<T, String> {
+    let (status, body) = get(endpoint, path).await?;
+    if !status.is_success() {
+        return Err(format!("HTTP {}", status));
+    }
+    serde_json::from_slice(&body).map_err(|e| e.to_string())
+}
+
+async fn request(
+    endpoint: &Endpoint,
+    method: Method,
+    path: &str,
+    json: Option<Vec<u8>>,
+) -> Result<(StatusCode, Bytes), String> {
+    let mut builder = Request::builder().method(method).uri(path);
+    if json.is_some() {
+        builder = builder.header(header::CONTENT_TYPE, "application/json");
+    }
+    let request = builder
+        .body(Full::new(Bytes::from(json.unwrap_or_default())))
+        .map_err(|e| e.to_string())?;
+    let response = send(endpoint, request).await?;
+    let status = response.status();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(|e| e.to_string())?
+        .to_bytes();
+    Ok((status, body))
+}
+
+/// Origin of `url` as browsers serialize it in `Origin` headers. Unlike
+/// [`Url::origin`], this also works for custom schemes such as `tauri:`.
+fn origin_of(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    match url.port() {
+        Some(port) => format!("{}://{}:{}", url.scheme(), host, port),
+        None => format!("{}://{}", url.scheme(), host),
+    }
+}
+
+/// Whether a request to the `api:` scheme comes from the app itself: one of
+/// its own webviews, and the app page rather than a frame inside it such as
+/// a web preview. Requests without an `Origin` header (images, downloads,
+/// no-cors fetches) carry none of their response back to another origin, so
+/// those are let through when they are reads and no referrer says otherwise.
+fn is_trusted(app: &AppHandle, webview_label: &str, request: &Request<Vec<u8>>) -> bool {
+    if webview_label != windows::MAIN_WINDOW_LABEL && webview_label != quick_prompt::WINDOW_LABEL {
+        return false;
+    }
+    let Some(app_origin) = app
+        .get_webview_window(webview_label)
+        .and_then(|webview| webview.url().ok())
+        .map(|url| origin_of(&url))
+    else {
+        return false;
+    };
+
+    let headers = request.headers();
+    if let Some(origin) = headers.get(header::ORIGIN) {
+        return origin.to_str().is_ok_and(|origin| origin == app_origin);
+    }
+    let safe = matches!(*request.method(), Method::GET | Method::HEAD);
+    match headers.get(header::REFERER) {
+        Some(referer) => {
+            safe && referer
+                .to_str()
+                .ok()
+                .and_then(|referer| Url::parse(referer).ok())
+                .is_some_and(|referer| origin_of(&referer) == app_origin)
+        }
+        None => safe,
+    }
+}
+
+fn forbidden() -> Response<Vec<u8>> {
+    Response::builder()
+        .status(StatusCode::FORBIDDEN)
+        .header(header::CONTENT_TYPE, "text/plain")
+        .body(b"Requests to the API must come from the OpenAsst app".to_vec())
+        .unwrap()
+}
+
+/// Handles a request to the `api:` scheme by forwarding it to the sidecar.
+/// Responses are buffered; streaming endpoints go through [`api_stream`].
+pub async fn handle(
+    app: &AppHandle,
+    webview_label: &str,
+    request: Request<Vec<u8>>,
+) -> Response<Vec<u8>> {
+    if !is_trusted(app, webview_label, &request) {
+        logs::warn(
+            app,
+            &format!(
+                "Refused {} {} from an untrusted page",
+                request.method(),
+                request.uri().path()
+            ),
+        );
+        return forbidden();
+    }
+    let supervisor = app.state::<Supervisor>();
+    let (parts, body) = request.into_parts();
+    let mutates = parts.method != Method::GET;
+    let path = parts.uri.path().to_string();
+    let request = Request::from_parts(parts, Full::new(Bytes::from(body)));
+
+    let result = match send(supervisor.endpoint(), request).await {
+        Ok(response) => {
+            let (parts, body) = response.into_parts();
+            body.collect()
+                .await
+                .map(|body| Response::from_parts(parts, body.to_bytes().to_vec()))
+                .map_err(|e| e.to_string())
+        }
+        Err(e) => Err(e),
+    };
+    // Let the tray pick up changes made in the webview right away.
+    if mutates && path.starts_with("/devices") {
+        app.state::<DeviceMonitor>().invalidate();
+    }
+    if mutates && path.starts_with("/hub") {
+        app.state::<HubMonitor>().invalidate();
+    }
+    if mutates && path.starts_with("/robots") {
+        app.state::<RobotMonitor>().invalidate();
+    }
+    if mutates && path.starts_with("/preview") {
+        app.state::<PreviewMonitor>().invalidate();
+    }
+
+    result.unwrap_or_else(|e| {
+        Response::builder()
+            .status(StatusCode::BAD_GATEWAY)
+            .header(header::CONTENT_TYPE, "text/plain")
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .body(e.into_bytes())
+            .unwrap()
+    })
+}
+
+/// A request the webview wants streamed back chunk by chunk.
+#[derive(Deserialize)]
+pub struct StreamRequest {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum StreamMessage {
+    Head {
+        status: u16,
+        headers: Vec<(String, String)>,
+    },
+    End,
+    Error {
+        message: String,
+    },
+}
+
+fn json(message: &StreamMessage) -> InvokeResponseBody {
+    InvokeResponseBody::Json(serde_json::to_string(message).unwrap_or_default())
+}
+
+/// Streams in flight, by webview and channel id, so the webview can cancel
+/// them with [`api_stream_cancel`].
+#[derive(Default)]
+pub struct Streams(Mutex<HashMap<(String, u32), Arc<Notify>>>);
+
+impl Streams {
+    /// The signal for a stream. A cancel that arrives before the stream
+    /// starts leaves a permit, so the stream stops as soon as it waits.
+    fn signal(&self, webview: &str, id: u32) -> Arc<Notify> {
+        self.0
+            .lock()
+            .unwrap()
+            .entry((webview.to_string(), id))
+            .or_default()
+            .clone()
+    }
+
+    fn remove(&self, webview: &str, id: u32) {
+        self.0.lock().unwrap().remove(&(webview.to_string(), id));
+    }
+}
+
+/// Forwards a request to the sidecar and streams the response over
+/// `channel`: a JSON `head` message, raw body chunks, then `end` or `error`.
+/// Used for SSE endpoints, which the buffered `api:` scheme cannot serve.
+/// Dropping the response when cancelled closes the upstream connection.
+#[tauri::command]
+pub async fn api_stream(
+    webview: tauri::Webview,
+    supervisor: tauri::State<'_, Supervisor>,
+    streams: tauri::State<'_, Streams>,
+    request: StreamRequest,
+    channel: Channel<InvokeResponseBody>,
+) -> Result<(), String> {
+    let label = webview.label().to_string();
+    let cancelled = streams.signal(&label, channel.id());
+    let result = tokio::select! {
+        result = stream(&supervisor, request, &channel) => result,
+        _ = cancelled.notified() => Ok(()),
+    };
+    streams.remove(&label, channel.id());
+    result
+}
+
+/// Stops the [`api_stream`] whose channel has `id`, e.g. because the page
+/// aborted the fetch. Unknown or finished streams are ignored.
+#[tauri::command]
+pub fn api_stream_cancel(webview: tauri::Webview, streams: tauri::State<'_, Streams>, id: u32) {
+    streams.signal(webview.label(), id).notify_one();
+}
+
+async fn stream(
+    supervisor: &Supervisor,
+    request: StreamRequest,
+    channel: &Channel<InvokeResponseBody>,
+) -> Result<(), String> {
+    let mut builder = Request::builder()
+        .method(request.method.as_str())
+        .uri(request.path.as_str());
+    for (name, value) in &request.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    let body = Full::new(Bytes::from(request.body.unwrap_or_default()));
+    let request = builder.body(body).map_err(|e| e.to_string())?;
+
+    let response = send(supervisor.endpoint(), request).await?;
+    let headers = response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    channel
+        .send(json(&StreamMessage::Head {
+            status: response.status().as_u16(),
+            headers,
+        }))
+        .map_err(|e| e.to_string())?;
+
+    let mut body = response.into_body();
+    while let Some(frame) = body.frame().await {
+        let sent = match frame {
+            Ok(frame) => match frame.into_data() {
+                Ok(data) => channel.send(InvokeResponseBody::Raw(data.to_vec())),
+                Err(_) => Ok(()),
+            },
+            Err(e) => {
+                let _ = channel.send(json(&StreamMessage::Error {
+                    message: e.to_string(),
+                }));
+                return Ok(());
+            }
+        };
+        // The webview went away or stopped listening.
+        if sent.is_err() {
+            return Ok(());
+        }
+    }
+
+    let _ = channel.send(json(&StreamMessage::End));
+    Ok(())
+}
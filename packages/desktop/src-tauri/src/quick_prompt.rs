@@ -0,0 +1,84 @@
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::deep_link::{self, DeepLink};
+use crate::{logs, settings, windows};
+
+/// Label of the quick prompt window (see `app.windows` in tauri.conf.json).
+pub const WINDOW_LABEL: &str = "quick-prompt";
+
+pub const DEFAULT_SHORTCUT: &str = "CommandOrControl+Shift+Space";
+
+/// Registers the configured shortcut at startup. A shortcut that cannot be
+/// registered, e.g. because another app holds it, only logs a warning.
+pub fn setup(app: &AppHandle) {
+    let shortcut = settings::current(app).quick_prompt_shortcut;
+    if let Err(e) = register(app, &shortcut) {
+        logs::warn(app, &e);
+    }
+}
+
+/// Switches from `previous` to `shortcut`, restoring `previous` if the new
+/// one cannot be registered.
+pub fn set_shortcut(app: &AppHandle, previous: &str, shortcut: &str) -> Result<(), String> {
+    unregister(app, previous);
+    register(app, shortcut).inspect_err(|_| {
+        let _ = register(app, previous);
+    })
+}
+
+fn register(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+    if shortcut.is_empty() {
+        return Ok(());
+    }
+    app.global_shortcut()
+        .on_shortcut(shortcut, |app, _, event| {
+            if event.state == ShortcutState::Pressed {
+                toggle(app);
+            }
+        })
+        .map_err(|e| format!("Cannot register shortcut {}: {}", shortcut, e))
+}
+
+fn unregister(app: &AppHandle, shortcut: &str) {
+    if !shortcut.is_empty() {
+        let _ = app.global_shortcut().unregister(shortcut);
+    }
+}
+
+/// Shows the quick prompt window, or hides it if it is in front already.
+pub fn toggle(app: &AppHandle) {
+    let window = match windows::get_or_create(app, WINDOW_LABEL) {
+        Ok(window) => window,
+        Err(e) => {
+            logs::error(app, &format!("Failed to open the quick prompt: {}", e));
+            return;
+        }
+    };
+    let focused = window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false);
+    if focused {
+        window.hide().unwrap_or_default();
+    } else {
+        window.center().unwrap_or_default();
+        window.show().unwrap_or_default();
+        window.set_focus().unwrap_or_default();
+    }
+}
+
+#[tauri::command]
+pub fn hide_quick_prompt(app: AppHandle) {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        window.hide().unwrap_or_default();
+    }
+}
+
+/// Hides the quick prompt and continues its conversation in the main window.
+/// Async because it may create the main window, which deadlocks in a
+/// synchronous command on Windows.
+#[tauri::command]
+pub async fn open_session_in_main_window(app: AppHandle, session_id: String) -> Result<(), String> {
+    let link = DeepLink::session(&session_id)?;
+    hide_quick_prompt(app.clone());
+    deep_link::open(&app, link);
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use serde::Deserialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+use tokio::sync::Notify;
+use tokio::time::{sleep, Duration};
+
+use crate::logs;
+use crate::proxy::{self, Endpoint};
+use crate::sidecar::Supervisor;
+use crate::tray;
+
+/// Live status goes over SSH to every robot's host, so it is polled slowly.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A deployed robot as listed by `GET /robots`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Robot {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    /// Last recorded state; replaced by the live state when it is known.
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+impl Robot {
+    pub fn is_running(&self) -> bool {
+        self.status.as_deref() == Some("running")
+    }
+
+    pub fn label(&self) -> &str {
+        if self.name.is_empty() {
+            &self.id
+        } else {
+            &self.name
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LiveStatus {
+    status: String,
+}
+
+#[derive(Deserialize)]
+struct Access {
+    url: String,
+}
+
+/// Managed state holding the robots last shown in the tray.
+pub struct RobotMonitor {
+    robots: Mutex<Option<Vec<Robot>>>,
+    changed: Notify,
+}
+
+impl RobotMonitor {
+    pub fn new() -> Self {
+        Self {
+            robots: Mutex::new(None),
+            changed: Notify::new(),
+        }
+    }
+
+    /// Asks [`watch`] to re-read the robots now.
+    pub fn invalidate(&self) {
+        self.changed.notify_one();
+    }
+
+    fn robot(&self, id: &str) -> Option<Robot> {
+        self.robots
+            .lock()
+            .unwrap()
+            .as_ref()?
+            .iter()
+            .find(|robot| robot.id == id)
+            .cloned()
+    }
+
+    async fn fetch(endpoint: &Endpoint) -> Result<Vec<Robot>, String> {
+        let mut robots: Vec<Robot> = timed(proxy::get_json(endpoint, "/robots")).await?;
+        for robot in &mut robots {
+            let path = format!("/robots/{}/status", robot.id);
+            if let Ok(live) = timed(proxy::get_json::<LiveStatus>(endpoint, &path)).await {
+                if live.status != "unknown" {
+                    robot.status = Some(live.status);
+                }
+            }
+        }
+        Ok(robots)
+    }
+}
+
+async fn timed<T>(
+    request: impl std::future::Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    tokio::time::timeout(REQUEST_TIMEOUT, request)
+        .await
+        .map_err(|_| "request timed out".to_string())?
+}
+
+/// Keeps the tray's robot submenu in sync with the API. Refreshes after
+/// robots are changed through the shell and on a slow poll otherwise.
+pub async fn watch(app: AppHandle) {
+    let monitor = app.state::<RobotMonitor>();
+    let supervisor = app.state::<Supervisor>();
+    loop {
+        if let Ok(robots) = RobotMonitor::fetch(supervisor.endpoint()).await {
+            let changed = {
+                let mut current = monitor.robots.lock().unwrap();
+                let changed = current.as_ref() != Some(&robots);
+                *current = Some(robots.clone());
+                changed
+            };
+            if changed {
+                tray::set_robots(&app, &robots);
+            }
+        }
+        tokio::select! {
+            _ = sleep(POLL_INTERVAL) => {}
+            _ = monitor.changed.notified() => {}
+        }
+    }
+}
+
+/// Starts the robot if it is stopped and stops it otherwise.
+pub fn toggle(app: &AppHandle, id: &str) {
+    let Some(robot) = app.state::<RobotMonitor>().robot(id) else {
+        return;
+    };
+    let action = if robot.is_running() { "stop" } else { "start" };
+    let path = format!("/robots/{}/{}", robot.id, action);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match proxy::post(app.state::<Supervisor>().endpoint(), &path).await {
+            Ok((status, _)) if status.is_success() => {
+                logs::info(
+                    &app,
+                    &format!("Robot {}: {} requested", robot.label(), action),
+                );
+            }
+            Ok((status, body)) => logs::error(
+                &app,
+                &format!(
+                    "POST {} failed: HTTP {} {}",
+                    path,
+                    status,
+                    String::from_utf8_lossy(&body)
+                ),
+            ),
+            Err(e) => logs::error(&app, &format!("POST {} failed: {}", path, e)),
+        }
+        // Rebuild the menu even if nothing changed, which resets the check
+        // mark the click flipped when the request failed.
+        let monitor = app.state::<RobotMonitor>();
+        *monitor.robots.lock().unwrap() = None;
+        monitor.invalidate();
+    });
+}
+
+/// Opens the robot's gateway in the default browser.
+pub fn open_access(app: &AppHandle, id: &str) {
+    let path = format!("/robots/{}/access", id);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let access = timed(proxy::get_json::<Access>(
+            app.state::<Supervisor>().endpoint(),
+            &path,
+        ))
+        .await;
+        let result = access.and_then(|access| {
+            app.opener()
+                .open_url(access.url, None::<&str>)
+                .map_err(|e| e.to_string())
+        });
+        if let Err(e) = result {
+            logs::error(&app, &format!("Failed to open robot access URL: {}", e));
+        }
+    });
+}
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::close::CloseBehavior;
+use crate::proxy::BackendTarget;
+use crate::{health, logs, profiles, quick_prompt, tray};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Preferences of the desktop shell itself, stored as JSON in the app config
+/// directory. Unknown or missing fields fall back to their defaults.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Settings {
+    /// Run the API server on the `node` found on PATH instead of the bundled
+    /// runtime.
+    pub prefer_system_node: bool,
+    /// Global shortcut summoning the quick prompt window, e.g.
+    /// `CommandOrControl+Shift+Space`. Empty disables it.
+    pub quick_prompt_shortcut: String,
+    /// What closing the main window does.
+    pub close_behavior: CloseBehavior,
+    /// Whether the first-close prompt has been answered.
+    pub close_prompt_shown: bool,
+    /// Start with only the sidecar and tray, like `--headless`.
+    pub headless: bool,
+    /// API server to use. Changing it restarts the app.
+    pub backend: BackendTarget,
+    /// Profile used when none is given with `--profile`; the last one
+    /// switched to.
+    pub profile: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            prefer_system_node: false,
+            quick_prompt_shortcut: quick_prompt::DEFAULT_SHORTCUT.to_string(),
+            close_behavior: CloseBehavior::default(),
+            close_prompt_shown: false,
+            headless: false,
+            backend: BackendTarget::default(),
+            profile: profiles::DEFAULT_PROFILE.to_string(),
+        }
+    }
+}
+
+/// Managed state holding the loaded settings and where they are saved.
+pub struct SettingsStore {
+    /// `None` in safe mode, where settings only last until the app quits.
+    path: Option<PathBuf>,
+    settings: Mutex<Settings>,
+}
+
+impl SettingsStore {
+    /// Loads settings from `dir`, using defaults if the file is missing or
+    /// unreadable.
+    pub fn load(dir: PathBuf) -> Self {
+        let path = dir.join(SETTINGS_FILE);
+        let settings = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            settings: Mutex::new(settings),
+        }
+    }
+
+    /// Default settings that are never written to disk, for `--safe-mode`.
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            settings: Mutex::new(Settings::default()),
+        }
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    /// Replaces the settings and writes them to disk.
+    pub fn set(&self, settings: Settings) -> std::io::Result<()> {
+        if let Some(path) = &self.path {
+            let contents = serde_json::to_string_pretty(&settings)?;
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, contents)?;
+        }
+        *self.settings.lock().unwrap() = settings;
+        Ok(())
+    }
+}
+
+/// Current settings, or the defaults before the store is set up.
+pub fn current(app: &AppHandle) -> Settings {
+    app.try_state::<SettingsStore>()
+        .map(|store| store.get())
+        .unwrap_or_default()
+}
+
+/// Settings as sent to the webview. The remote server's token stays in the
+/// shell; the webview only learns whether one is set.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsView {
+    #[serde(flatten)]
+    settings: Settings,
+    remote_token_set: bool,
+}
+
+#[tauri::command]
+pub fn get_settings(store: tauri::State<'_, SettingsStore>) -> SettingsView {
+    let mut settings = store.get();
+    let remote_token_set = match &mut settings.backend {
+        BackendTarget::Remote { token, .. } => !std::mem::take(token).is_empty(),
+        BackendTarget::Local => false,
+    };
+    SettingsView {
+        settings,
+        remote_token_set,
+    }
+}
+
+/// Saves `settings`. A remote backend sent without a token keeps the stored
+/// one if its URL is unchanged, since [`get_settings`] never returns it. A
+/// new remote backend is checked first and refused if it is unreachable or
+/// rejects its token; switching backends then restarts the app.
+#[tauri::command]
+pub async fn set_settings(
+    app: AppHandle,
+    store: tauri::State<'_, SettingsStore>,
+    mut settings: Settings,
+) -> Result<(), String> {
+    let previous = store.get();
+    if let (
+        BackendTarget::Remote { url, token },
+        BackendTarget::Remote {
+            url: previous_url,
+            token: previous_token,
+        },
+    ) = (&mut settings.backend, &previous.backend)
+    {
+        if token.is_empty() && url == previous_url {
+            token.clone_from(previous_token);
+        }
+    }
+    let backend_changed = settings.backend != previous.backend;
+    if backend_changed {
+        if let Some(endpoint) = settings.backend.remote_endpoint()? {
+            health::check_remote(&app, &endpoint).await?;
+        }
+    }
+    if settings.quick_prompt_shortcut != previous.quick_prompt_shortcut {
+        quick_prompt::set_shortcut(
+            &app,
+            &previous.quick_prompt_shortcut,
+            &settings.quick_prompt_shortcut,
+        )?;
+    }
+    let close_changed = settings.close_behavior != previous.close_behavior;
+    store.set(settings).map_err(|e| {
+        logs::error(&app, &format!("Failed to save settings: {}", e));
+        e.to_string()
+    })?;
+    if close_changed {
+        tray::set_close_behavior(&app);
+    }
+    if backend_changed {
+        logs::info(&app, "Backend changed, restarting");
+        app.request_restart();
+    }
+    Ok(())
+}
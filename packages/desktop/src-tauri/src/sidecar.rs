@@ -1,62 +1,643 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use tauri::{AppHandle, Manager};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::net::TcpStream;
-use tokio::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
 use tokio::time::{sleep, Duration};
 
+use crate::health::{self, ApiHealth, HealthMonitor};
+use crate::launch::{self, LaunchError};
+use crate::logs::{self, Level, Source};
+use crate::previews;
+use crate::profiles::Profiles;
+use crate::proxy::{self, Endpoint};
+use crate::tray;
+
+/// Event emitted to the frontend whenever the sidecar state changes.
+pub const STATE_EVENT: &str = "sidecar://state";
+
+/// Header carrying the per-launch secret the API requires on every request.
+pub const TOKEN_HEADER: &str = "X-OpenAsst-Token";
+
+/// Generates the random secret shared with the sidecar for this launch.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).expect("failed to generate API token");
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Lifecycle of the supervised API server process.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum SidecarState {
+    /// Development mode: the API server is started separately.
+    External,
+    Starting {
+        attempt: u32,
+    },
+    Running {
+        pid: Option<u32>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Restarting {
+        attempt: u32,
+        exit_code: Option<i32>,
+        delay_ms: u64,
+    },
+    /// The supervisor gave up; the backend stays down until the app restarts.
+    Failed {
+        reason: String,
+    },
+    /// Terminated on purpose because the desktop app is exiting.
+    Stopped,
+}
+
+/// Restart policy for the sidecar.
+#[derive(Clone, Debug)]
+pub struct SupervisorConfig {
+    /// Crashes tolerated within `crash_window` before giving up.
+    pub max_crashes: usize,
+    pub crash_window: Duration,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Time between SIGTERM and SIGKILL when shutting the sidecar down.
+    pub shutdown_grace: Duration,
+    /// The API server is started separately and only watched, as with
+    /// `pnpm dev:api` in development or `--api-url`.
+    pub external: bool,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            max_crashes: 5,
+            crash_window: Duration::from_secs(60),
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            shutdown_grace: Duration::from_secs(5),
+            external: cfg!(debug_assertions),
+        }
+    }
+}
+
+impl SupervisorConfig {
+    /// Default policy, with the crash-loop threshold overridable through
+    /// `OPENASST_SIDECAR_MAX_CRASHES`.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Some(n) = std::env::var("OPENASST_SIDECAR_MAX_CRASHES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            config.max_crashes = n;
+        }
+        config
+    }
+
+    fn backoff(&self, crashes: usize) -> Duration {
+        let factor = 1u32 << crashes.saturating_sub(1).min(16);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Managed state owning the sidecar's restart policy and current state.
+pub struct Supervisor {
+    config: SupervisorConfig,
+    endpoint: Endpoint,
+    state: Mutex<SidecarState>,
+    /// Pid of the live child, which is also its process group id on Unix.
+    pid: Mutex<Option<u32>>,
+    /// Signalled when the live child has exited and `pid` is cleared.
+    exited: Condvar,
+    /// When the live child passed its readiness check.
+    running_since: Mutex<Option<Instant>>,
+    shutting_down: AtomicBool,
+    /// A supervise loop is running; at most one may own the child.
+    supervising: AtomicBool,
+    /// The next exit was requested by the user and is not a crash.
+    restart_requested: AtomicBool,
+}
+
+impl Supervisor {
+    pub fn new(config: SupervisorConfig, endpoint: Endpoint) -> Self {
+        Self {
+            config,
+            endpoint,
+            state: Mutex::new(SidecarState::Starting { attempt: 0 }),
+            pid: Mutex::new(None),
+            exited: Condvar::new(),
+            running_since: Mutex::new(None),
+            shutting_down: AtomicBool::new(false),
+            supervising: AtomicBool::new(false),
+            restart_requested: AtomicBool::new(false),
+        }
+    }
+
+    /// Restarts the API server on user request. A running server is
+    /// terminated and respawned right away without counting as a crash; if
+    /// the supervisor already gave up, supervision starts over. Safe to call
+    /// repeatedly: only the first call after a failure starts a new loop.
+    pub fn restart(&self, app: &AppHandle) {
+        let state = {
+            let mut state = self.state.lock().unwrap();
+            let current = state.clone();
+            // Leave Failed under the lock so a second call does not also
+            // start supervising
+            if matches!(current, SidecarState::Failed { .. }) {
+                *state = SidecarState::Starting { attempt: 0 };
+            }
+            current
+        };
+        match state {
+            SidecarState::External | SidecarState::Stopped => {}
+            SidecarState::Failed { .. } => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = start_api_server(&app).await {
+                        logs::error(&app, &format!("Failed to start API server: {}", e));
+                    }
+                });
+            }
+            _ => {
+                let Some(pid) = *self.pid.lock().unwrap() else {
+                    return;
+                };
+                logs::info(app, &format!("Restarting API server (pid {})", pid));
+                self.restart_requested.store(true, Ordering::SeqCst);
+                if !process_tree::terminate(pid) {
+                    process_tree::kill(pid);
+                    return;
+                }
+
+                let app = app.clone();
+                let grace = self.config.shutdown_grace;
+                tauri::async_runtime::spawn(async move {
+                    sleep(grace).await;
+                    if *app.state::<Supervisor>().pid.lock().unwrap() == Some(pid) {
+                        process_tree::kill(pid);
+                    }
+                });
+            }
+        }
+    }
+
+    /// Pid of the live child, if there is one.
+    pub fn pid(&self) -> Option<u32> {
+        *self.pid.lock().unwrap()
+    }
+
+    /// How long the live child has been up, if it is running.
+    pub fn uptime(&self) -> Option<Duration> {
+        self.running_since.lock().unwrap().map(|t| t.elapsed())
+    }
+
+    /// Stops supervising and terminates the sidecar together with every
+    /// process it spawned (preview servers, robots). Sends SIGTERM to the
+    /// process group, waits up to `shutdown_grace` for the sidecar to exit,
+    /// then SIGKILLs whatever is left. Where there is no graceful signal, as
+    /// on Windows, the tree is killed right away. The API is first asked to
+    /// stop its preview servers. Blocks the calling thread.
+    pub fn shutdown(&self, app: &AppHandle) {
+        if self.shutting_down.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let Some(pid) = *self.pid.lock().unwrap() else {
+            return;
+        };
+
+        previews::stop_all_before_exit(app, self);
+
+        logs::info(app, &format!("Stopping API server (pid {})", pid));
+        if process_tree::terminate(pid) {
+            let _ = self
+                .exited
+                .wait_timeout_while(
+                    self.pid.lock().unwrap(),
+                    self.config.shutdown_grace,
+                    |pid| pid.is_some(),
+                )
+                .unwrap();
+        }
+
+        process_tree::kill(pid);
+
+        #[cfg(unix)]
+        if let Endpoint::Socket { path, .. } = &self.endpoint {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    /// Script run in every webview before the page loads. Exposes the API
+    /// location as `window.__OPENASST__`, and routes API requests that ask
+    /// for `text/event-stream` through the streaming `api_stream` command,
+    /// since the `api:` scheme buffers whole responses. Aborting such a
+    /// request, or cancelling its body, cancels the command as well.
+    pub fn init_script() -> String {
+        let env = serde_json::json!({ "apiBaseUrl": proxy::webview_base_url() });
+        include_str!("init.js").replace("__OPENASST_ENV__", &env.to_string())
+    }
+
+    pub fn state(&self) -> SidecarState {
+        self.state.lock().unwrap().clone()
+    }
+
+    fn set_state(&self, app: &AppHandle, state: SidecarState) {
+        *self.state.lock().unwrap() = state.clone();
+        let _ = app.emit(STATE_EVENT, state);
+        tray::refresh(app);
+    }
+}
+
+#[tauri::command]
+pub fn get_sidecar_state(supervisor: tauri::State<'_, Supervisor>) -> SidecarState {
+    supervisor.state()
+}
+
+#[tauri::command]
+pub fn get_api_base_url() -> String {
+    proxy::webview_base_url()
+}
+
 pub async fn start_api_server(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let resource_dir = app
         .path()
         .resource_dir()
-        .unwrap_or_else(|_| std::path::PathBuf::from("."));
+        .unwrap_or_else(|_| PathBuf::from("."));
 
     let api_entry = resource_dir.join("api-server").join("index.js");
 
-    // In development, the API server is started separately
-    if cfg!(debug_assertions) {
-        println!("Development mode: API server should be started separately with `pnpm dev:api`");
+    let supervisor = app.state::<Supervisor>();
+    if supervisor.supervising.swap(true, Ordering::SeqCst) {
+        logs::info(app, "The API server is already supervised");
+        return Ok(());
+    }
+    if supervisor.config.external {
+        if cfg!(debug_assertions) && !supervisor.endpoint.is_remote() {
+            logs::info(
+                app,
+                "Development mode: API server should be started separately with `pnpm dev:api`",
+            );
+        } else {
+            logs::info(
+                app,
+                &format!("Using the API server on {}", supervisor.endpoint.describe()),
+            );
+        }
+        supervisor.set_state(app, SidecarState::External);
+        health::watch(app).await;
         return Ok(());
     }
 
-    println!("Starting API server from: {:?}", api_entry);
+    supervise(app.clone(), api_entry).await;
+    Ok(())
+}
 
-    let mut child = Command::new("node")
-        .arg(&api_entry)
+/// Keeps the API server running, restarting it with exponential backoff
+/// until it crashes more than `max_crashes` times within `crash_window`.
+/// Failures that a restart cannot fix are reported to the user instead.
+async fn supervise(app: AppHandle, api_entry: PathBuf) {
+    let supervisor = app.state::<Supervisor>();
+    let result = supervise_until_failure(&app, &supervisor, &api_entry).await;
+    // Released before the state says Failed, so a restart seeing it can
+    // start over
+    supervisor.supervising.store(false, Ordering::SeqCst);
+    if let Err(e) = result {
+        // Failed first, so the dialog's Retry finds something to restart
+        supervisor.set_state(
+            &app,
+            SidecarState::Failed {
+                reason: e.to_string(),
+            },
+        );
+        launch::report(&app, &e);
+    }
+}
+
+async fn supervise_until_failure(
+    app: &AppHandle,
+    supervisor: &Supervisor,
+    api_entry: &Path,
+) -> Result<(), LaunchError> {
+    let config = supervisor.config.clone();
+    let mut crashes: VecDeque<Instant> = VecDeque::new();
+    let mut attempt = 0;
+
+    supervisor.set_state(app, SidecarState::Starting { attempt: 1 });
+    let node = launch::preflight(app, api_entry).await?;
+
+    loop {
+        attempt += 1;
+        supervisor.set_state(app, SidecarState::Starting { attempt });
+
+        let child = spawn_node(app, &node, api_entry, supervisor)?;
+        let exit = run_until_exit(app, supervisor, child).await?;
+        let exit_code = exit.code;
+
+        if supervisor.is_shutting_down() {
+            supervisor.set_state(app, SidecarState::Stopped);
+            return Ok(());
+        }
+        app.state::<HealthMonitor>()
+            .update(app, ApiHealth::down("API server exited"));
+
+        if supervisor.restart_requested.swap(false, Ordering::SeqCst) {
+            crashes.clear();
+            attempt = 0;
+            continue;
+        }
+
+        // A server that dies before ever answering on its first run is
+        // misconfigured rather than flaky; restarting will not help.
+        if attempt == 1 && !exit.was_ready {
+            return Err(LaunchError::ExitedOnStartup(exit_code));
+        }
+
+        let now = Instant::now();
+        crashes.push_back(now);
+        while crashes
+            .front()
+            .is_some_and(|t| now.duration_since(*t) > config.crash_window)
+        {
+            crashes.pop_front();
+        }
+
+        if crashes.len() > config.max_crashes {
+            return Err(LaunchError::CrashLoop {
+                crashes: crashes.len(),
+                window_secs: config.crash_window.as_secs(),
+            });
+        }
+
+        let delay = config.backoff(crashes.len());
+        logs::warn(
+            app,
+            &format!(
+                "API server exited (code {:?}), restarting in {}ms",
+                exit_code,
+                delay.as_millis()
+            ),
+        );
+        supervisor.set_state(
+            app,
+            SidecarState::Restarting {
+                attempt,
+                exit_code,
+                delay_ms: delay.as_millis() as u64,
+            },
+        );
+        sleep(delay).await;
+    }
+}
+
+fn spawn_node(
+    app: &AppHandle,
+    node: &Path,
+    api_entry: &Path,
+    supervisor: &Supervisor,
+) -> std::io::Result<Child> {
+    logs::info(app, &format!("Starting API server from: {:?}", api_entry));
+
+    let mut command = Command::new(node);
+    command
+        .arg(api_entry)
         .env("NODE_ENV", "production")
+        .env("OPENASST_API_TOKEN", supervisor.endpoint.token())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .spawn()?;
+        .kill_on_drop(true);
+
+    // Put the sidecar in its own process group so shutdown can signal the
+    // whole tree it spawns.
+    #[cfg(unix)]
+    command.process_group(0);
+
+    match &supervisor.endpoint {
+        Endpoint::Tcp { port, .. } => command.env("PORT", port.to_string()),
+        Endpoint::Socket { path, .. } => command.env("OPENASST_API_SOCKET", path),
+        // Remote servers are external and never spawned
+        Endpoint::Remote { .. } => &mut command,
+    };
+    for (name, dir) in app.state::<Profiles>().env() {
+        command.env(name, dir);
+    }
+
+    let mut child = command.spawn()?;
 
     if let Some(stdout) = child.stdout.take() {
-        tauri::async_runtime::spawn(async move {
-            let reader = BufReader::new(stdout);
-            let mut lines = reader.lines();
-            while let Ok(Some(line)) = lines.next_line().await {
-                println!("[API] {}", line);
+        tauri::async_runtime::spawn(forward_output(app.clone(), stdout, Level::Info));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tauri::async_runtime::spawn(forward_output(app.clone(), stderr, Level::Error));
+    }
+
+    Ok(child)
+}
+
+/// Logs each line the sidecar writes to `output` until the pipe closes.
+/// Lines are read as bytes so output that is not valid UTF-8 is logged
+/// lossily instead of ending the loop and leaving the pipe undrained, which
+/// would block Node once the pipe buffer fills.
+async fn forward_output(app: AppHandle, output: impl AsyncRead + Unpin, level: Level) {
+    let mut reader = BufReader::new(output);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                let text = String::from_utf8_lossy(&line);
+                logs::log(
+                    &app,
+                    level,
+                    Source::Api,
+                    text.trim_end_matches(['\r', '\n']),
+                );
+            }
+            Err(e) => {
+                logs::warn(&app, &format!("Failed to read API server output: {}", e));
+                break;
             }
-        });
+        }
     }
+}
 
-    if let Some(stderr) = child.stderr.take() {
-        tauri::async_runtime::spawn(async move {
-            let reader = BufReader::new(stderr);
-            let mut lines = reader.lines();
-            while let Ok(Some(line)) = lines.next_line().await {
-                eprintln!("[API ERR] {}", line);
+/// How a sidecar process ended.
+struct Exit {
+    code: Option<i32>,
+    /// Whether the process got past the readiness check before exiting.
+    was_ready: bool,
+}
+
+/// Waits for the child to become ready, then for it to exit. Fails if the
+/// server turned out to be unusable.
+async fn run_until_exit(
+    app: &AppHandle,
+    supervisor: &Supervisor,
+    mut child: Child,
+) -> Result<Exit, LaunchError> {
+    let pid = child.id();
+    *supervisor.pid.lock().unwrap() = pid;
+
+    let exit = run_child(app, supervisor, &mut child, pid).await;
+    *supervisor.pid.lock().unwrap() = None;
+    supervisor.exited.notify_all();
+    *supervisor.running_since.lock().unwrap() = None;
+    exit
+}
+
+/// Reports the server as running once it first answers healthy, which may
+/// be after `wait_until_healthy` gave up once; until then it stays starting
+/// and the health shown says it has not come up. Then watches health until
+/// the child exits.
+async fn run_child(
+    app: &AppHandle,
+    supervisor: &Supervisor,
+    child: &mut Child,
+    pid: Option<u32>,
+) -> Result<Exit, LaunchError> {
+    let mut ready = false;
+    let outcome = tokio::select! {
+        status = child.wait() => Ok(status.ok().and_then(|s| s.code())),
+        error = async {
+            loop {
+                let health = health::wait_until_healthy(app).await;
+                if health.incompatible {
+                    return LaunchError::Incompatible(health.error.unwrap_or_default());
+                }
+                if health.healthy {
+                    if let Some(warning) = &health.warning {
+                        logs::warn(app, &format!("API server running in degraded mode: {}", warning));
+                    }
+                    break;
+                }
+                let error = format!(
+                    "API server has not reported healthy yet: {}",
+                    health.error.unwrap_or_default()
+                );
+                logs::warn(app, &error);
+                app.state::<HealthMonitor>().update(app, ApiHealth::down(error));
             }
-        });
+            ready = true;
+            logs::info(
+                app,
+                &format!("API server is ready on {}", supervisor.endpoint.describe()),
+            );
+            *supervisor.running_since.lock().unwrap() = Some(Instant::now());
+            supervisor.set_state(app, SidecarState::Running { pid });
+            health::watch(app).await;
+            unreachable!("health::watch never returns")
+        } => Err(error),
+    };
+
+    match outcome {
+        Ok(code) => Ok(Exit {
+            code,
+            was_ready: ready,
+        }),
+        Err(error) => {
+            let _ = child.kill().await;
+            Err(error)
+        }
     }
+}
 
-    // Wait for server to be ready via TCP connect
-    for _ in 0..30 {
-        sleep(Duration::from_millis(500)).await;
-        if TcpStream::connect("127.0.0.1:2620").await.is_ok() {
-            println!("API server is ready on port 2620");
-            return Ok(());
+#[cfg(unix)]
+mod process_tree {
+    /// Sends SIGTERM to the process group led by `pid`. Returns whether a
+    /// graceful stop was requested, which is always the case here.
+    pub fn terminate(pid: u32) -> bool {
+        signal_group(pid, libc::SIGTERM);
+        true
+    }
+
+    /// Sends SIGKILL to the process group led by `pid`.
+    pub fn kill(pid: u32) {
+        signal_group(pid, libc::SIGKILL);
+    }
+
+    fn signal_group(pid: u32, signal: libc::c_int) {
+        // SAFETY: kill(2) has no memory-safety preconditions; a negative pid
+        // addresses the process group.
+        unsafe {
+            libc::kill(-(pid as libc::pid_t), signal);
         }
     }
+}
 
-    println!("API server started (health check timed out, continuing anyway)");
-    Ok(())
+#[cfg(windows)]
+mod process_tree {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    /// Windows has no SIGTERM for console processes, so nothing is sent and
+    /// callers kill the tree straight away.
+    pub fn terminate(_pid: u32) -> bool {
+        false
+    }
+
+    /// Kills `pid` and all of its descendants.
+    pub fn kill(pid: u32) {
+        let _ = Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .status();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SupervisorConfig {
+        SupervisorConfig {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            ..SupervisorConfig::default()
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_per_crash() {
+        let config = config();
+        assert_eq!(config.backoff(0), Duration::from_millis(500));
+        assert_eq!(config.backoff(1), Duration::from_millis(500));
+        assert_eq!(config.backoff(2), Duration::from_secs(1));
+        assert_eq!(config.backoff(3), Duration::from_secs(2));
+        assert_eq!(config.backoff(6), Duration::from_secs(16));
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let config = config();
+        assert_eq!(config.backoff(7), Duration::from_secs(30));
+        assert_eq!(config.backoff(64), Duration::from_secs(30));
+        assert_eq!(config.backoff(usize::MAX), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn backoff_does_not_overflow_long_delays() {
+        let config = SupervisorConfig {
+            initial_backoff: Duration::MAX,
+            max_backoff: Duration::MAX,
+            ..SupervisorConfig::default()
+        };
+        assert_eq!(config.backoff(usize::MAX), Duration::MAX);
+    }
 }
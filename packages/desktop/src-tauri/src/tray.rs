@@ -1,30 +1,544 @@
+use std::time::Duration;
 use tauri::{
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::TrayIconBuilder,
-    App, Manager,
+    App, AppHandle, Manager, Wry,
 };
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::close::{self, CloseBehavior};
+use crate::devices::{self, Device, DeviceTree};
+use crate::health::HealthMonitor;
+use crate::hub::{self, HubStatus};
+use crate::logs::{self, Logger};
+use crate::previews::{self, Preview};
+use crate::profiles::{self, ProfileList, Profiles};
+use crate::robots::{self, Robot};
+use crate::settings;
+use crate::sidecar::{SidecarState, Supervisor};
+use crate::windows;
+
+const TRAY_ID: &str = "main";
+
+/// Prefix of the menu ids of device entries, followed by the device id.
+const DEVICE_ID_PREFIX: &str = "device:";
+/// Prefixes of the menu ids of robot actions, followed by the robot id.
+const ROBOT_TOGGLE_PREFIX: &str = "robot-toggle:";
+const ROBOT_ACCESS_PREFIX: &str = "robot-access:";
+/// Prefixes of the menu ids of preview actions, followed by the task id.
+const PREVIEW_OPEN_PREFIX: &str = "preview-open:";
+const PREVIEW_STOP_PREFIX: &str = "preview-stop:";
+/// Prefix of the menu ids of profile entries, followed by the profile name.
+const PROFILE_PREFIX: &str = "profile:";
+
+/// How often the menu is refreshed so the uptime stays current.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Log lines included in the copied diagnostics.
+const DIAGNOSTICS_LOG_LINES: usize = 20;
+
+/// Managed state holding the tray menu items that change at runtime.
+struct TrayMenu {
+    status: MenuItem<Wry>,
+    endpoint: MenuItem<Wry>,
+    restart: MenuItem<Wry>,
+    devices: Submenu<Wry>,
+    robots: Submenu<Wry>,
+    previews: Submenu<Wry>,
+    hub_status: MenuItem<Wry>,
+    hub_toggle: MenuItem<Wry>,
+    profiles: Submenu<Wry>,
+    close_tray: CheckMenuItem<Wry>,
+    close_quit: CheckMenuItem<Wry>,
+    close_ask: CheckMenuItem<Wry>,
+}
 
 pub fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
+    let status = MenuItem::with_id(app, "status", "Starting…", false, None::<&str>)?;
+    let endpoint = MenuItem::with_id(app, "endpoint", "", false, None::<&str>)?;
     let show = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
+    let devices = Submenu::with_items(
+        app,
+        "Devices",
+        true,
+        &[&MenuItem::new(app, "Loading…", false, None::<&str>)?],
+    )?;
+    let robots = Submenu::with_items(
+        app,
+        "Robots",
+        true,
+        &[&MenuItem::new(app, "Loading…", false, None::<&str>)?],
+    )?;
+    let previews = Submenu::with_items(
+        app,
+        "Previews",
+        true,
+        &[&MenuItem::new(app, "Loading…", false, None::<&str>)?],
+    )?;
+    let hub_status = MenuItem::with_id(app, "hub_status", "Hub: unknown", false, None::<&str>)?;
+    let hub_toggle = MenuItem::with_id(app, "hub_toggle", "Start Hub", false, None::<&str>)?;
+    let profiles = Submenu::new(app, "Profile", true)?;
+    let close_tray = CheckMenuItem::with_id(
+        app,
+        "close_tray",
+        "Keep Running in Tray",
+        true,
+        false,
+        None::<&str>,
+    )?;
+    let close_quit = CheckMenuItem::with_id(app, "close_quit", "Quit", true, false, None::<&str>)?;
+    let close_ask = CheckMenuItem::with_id(app, "close_ask", "Ask", true, false, None::<&str>)?;
+    let on_close = Submenu::with_items(
+        app,
+        "When the Window Closes",
+        true,
+        &[&close_tray, &close_quit, &close_ask],
+    )?;
+    let restart = MenuItem::with_id(app, "restart", "Restart API Server", true, None::<&str>)?;
+    let open_logs = MenuItem::with_id(app, "open_logs", "Open Logs Folder", true, None::<&str>)?;
+    let copy_diagnostics = MenuItem::with_id(
+        app,
+        "copy_diagnostics",
+        "Copy Diagnostics",
+        true,
+        None::<&str>,
+    )?;
     let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-    let menu = Menu::with_items(app, &[&show, &quit])?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &status,
+            &endpoint,
+            &PredefinedMenuItem::separator(app)?,
+            &show,
+            &devices,
+            &robots,
+            &previews,
+            &PredefinedMenuItem::separator(app)?,
+            &hub_status,
+            &hub_toggle,
+            &PredefinedMenuItem::separator(app)?,
+            &profiles,
+            &on_close,
+            &restart,
+            &open_logs,
+            &copy_diagnostics,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )?;
 
-    TrayIconBuilder::new()
+    TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu)
+        .tooltip("OpenAsst")
         .show_menu_on_left_click(false)
         .on_menu_event(|app, event| match event.id.as_ref() {
             "show" => {
-                if let Some(window) = app.get_webview_window("main") {
-                    window.show().unwrap_or_default();
-                    window.set_focus().unwrap_or_default();
+                show_main_window(app);
+            }
+            "preview_stop_all" => {
+                previews::stop_all(app);
+            }
+            "hub_toggle" => {
+                hub::toggle(app);
+            }
+            "close_tray" => close::set_behavior(app, CloseBehavior::Tray),
+            "close_quit" => close::set_behavior(app, CloseBehavior::Quit),
+            "close_ask" => close::set_behavior(app, CloseBehavior::Ask),
+            "restart" => {
+                app.state::<Supervisor>().restart(app);
+            }
+            "open_logs" => {
+                let dir = app.state::<Logger>().dir().to_path_buf();
+                let _ = std::fs::create_dir_all(&dir);
+                if let Err(e) = app.opener().open_path(dir.to_string_lossy(), None::<&str>) {
+                    logs::error(app, &format!("Failed to open logs folder: {}", e));
+                }
+            }
+            "copy_diagnostics" => {
+                if let Err(e) = app.clipboard().write_text(diagnostics(app)) {
+                    logs::error(app, &format!("Failed to copy diagnostics: {}", e));
                 }
             }
             "quit" => {
                 app.exit(0);
             }
-            _ => {}
+            id => {
+                if let Some(device_id) = id.strip_prefix(DEVICE_ID_PREFIX) {
+                    devices::open(app, device_id);
+                } else if let Some(robot_id) = id.strip_prefix(ROBOT_TOGGLE_PREFIX) {
+                    robots::toggle(app, robot_id);
+                } else if let Some(robot_id) = id.strip_prefix(ROBOT_ACCESS_PREFIX) {
+                    robots::open_access(app, robot_id);
+                } else if let Some(task_id) = id.strip_prefix(PREVIEW_OPEN_PREFIX) {
+                    previews::open(app, task_id);
+                } else if let Some(task_id) = id.strip_prefix(PREVIEW_STOP_PREFIX) {
+                    previews::stop(app, task_id);
+                } else if let Some(name) = id.strip_prefix(PROFILE_PREFIX) {
+                    if let Err(e) = profiles::switch(app, name) {
+                        logs::error(app, &format!("Cannot switch profile: {}", e));
+                    }
+                }
+            }
         })
         .build(app)?;
 
+    app.manage(TrayMenu {
+        status,
+        endpoint,
+        restart,
+        devices,
+        robots,
+        previews,
+        hub_status,
+        hub_toggle,
+        profiles,
+        close_tray,
+        close_quit,
+        close_ask,
+    });
+    refresh(app.handle());
+    set_profiles(app.handle());
+    set_close_behavior(app.handle());
+
+    let handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(REFRESH_INTERVAL).await;
+            refresh(&handle);
+        }
+    });
+
     Ok(())
 }
+
+/// Shows, restores and focuses the main window, creating it first when the
+/// app runs headless or the window was closed.
+pub fn show_main_window(app: &AppHandle) {
+    match windows::get_or_create(app, windows::MAIN_WINDOW_LABEL) {
+        Ok(window) => {
+            window.show().unwrap_or_default();
+            window.unminimize().unwrap_or_default();
+            window.set_focus().unwrap_or_default();
+        }
+        Err(e) => logs::error(app, &format!("Failed to open the main window: {}", e)),
+    }
+}
+
+/// Updates the status lines, actions and tooltip from the supervisor state and
+/// last known health. Does nothing until the tray is set up.
+pub fn refresh(app: &AppHandle) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    let supervisor = app.state::<Supervisor>();
+    let health = app.state::<HealthMonitor>().health();
+
+    let status = match supervisor.state() {
+        SidecarState::External if supervisor.endpoint().is_remote() => {
+            format!("Remote server — {}", health.summary())
+        }
+        SidecarState::External if cfg!(debug_assertions) => {
+            format!("Dev server — {}", health.summary())
+        }
+        SidecarState::External => format!("External server — {}", health.summary()),
+        SidecarState::Starting { attempt } if attempt > 1 => {
+            format!("Starting (attempt {})…", attempt)
+        }
+        SidecarState::Starting { .. } => "Starting…".to_string(),
+        SidecarState::Running { .. } => match supervisor.uptime() {
+            Some(uptime) => format!("{} — up {}", health.summary(), format_uptime(uptime)),
+            None => health.summary(),
+        },
+        SidecarState::Restarting { exit_code, .. } => match exit_code {
+            Some(code) => format!("Crashed (exit code {}), restarting…", code),
+            None => "Crashed, restarting…".to_string(),
+        },
+        SidecarState::Failed { .. } => "Stopped after failure".to_string(),
+        SidecarState::Stopped => "Stopped".to_string(),
+    };
+    let restart_label = match supervisor.state() {
+        SidecarState::Failed { .. } => "Start API Server",
+        _ => "Restart API Server",
+    };
+    let can_restart = !matches!(
+        supervisor.state(),
+        SidecarState::External | SidecarState::Stopped
+    );
+
+    let _ = menu.status.set_text(&status);
+    let _ = menu
+        .endpoint
+        .set_text(format!("API on {}", supervisor.endpoint().describe()));
+    let _ = menu.restart.set_text(restart_label);
+    let _ = menu.restart.set_enabled(can_restart);
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(format!("OpenAsst — {}", status)));
+    }
+}
+
+/// Shows the hub status and offers to start or stop it. `None` means the API
+/// could not be asked.
+pub fn set_hub(app: &AppHandle, status: Option<&HubStatus>) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    let (text, toggle) = match status {
+        Some(status) if status.running => (status.summary(), "Stop Hub"),
+        Some(status) => (status.summary(), "Start Hub"),
+        None => ("Hub: unavailable".to_string(), "Start Hub"),
+    };
+    let _ = menu.hub_status.set_text(text);
+    let _ = menu.hub_toggle.set_text(toggle);
+    let _ = menu.hub_toggle.set_enabled(status.is_some());
+}
+
+/// Rebuilds the Devices submenu: one nested submenu per group, then the
+/// ungrouped devices.
+pub fn set_devices(app: &AppHandle, tree: &DeviceTree) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    if let Err(e) = fill_devices(app, &menu.devices, tree) {
+        logs::warn(
+            app,
+            &format!("Failed to update the tray device list: {}", e),
+        );
+    }
+}
+
+fn fill_devices(app: &AppHandle, submenu: &Submenu<Wry>, tree: &DeviceTree) -> tauri::Result<()> {
+    while submenu.remove_at(0)?.is_some() {}
+
+    if tree.is_empty() {
+        submenu.append(&MenuItem::new(app, "No devices", false, None::<&str>)?)?;
+        return Ok(());
+    }
+
+    for (name, devices) in &tree.groups {
+        let group = Submenu::new(app, name, true)?;
+        for device in devices {
+            group.append(&device_item(app, device)?)?;
+        }
+        submenu.append(&group)?;
+    }
+    if !tree.groups.is_empty() && !tree.ungrouped.is_empty() {
+        submenu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+    for device in &tree.ungrouped {
+        submenu.append(&device_item(app, device)?)?;
+    }
+    Ok(())
+}
+
+fn device_item(app: &AppHandle, device: &Device) -> tauri::Result<impl IsMenuItem<Wry>> {
+    MenuItem::with_id(
+        app,
+        format!("{}{}", DEVICE_ID_PREFIX, device.id),
+        &device.label,
+        true,
+        None::<&str>,
+    )
+}
+
+/// Rebuilds the Robots submenu: one entry per robot with a running toggle and
+/// a link to its gateway.
+pub fn set_robots(app: &AppHandle, robots: &[Robot]) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    if let Err(e) = fill_robots(app, &menu.robots, robots) {
+        logs::warn(app, &format!("Failed to update the tray robot list: {}", e));
+    }
+}
+
+fn fill_robots(app: &AppHandle, submenu: &Submenu<Wry>, robots: &[Robot]) -> tauri::Result<()> {
+    while submenu.remove_at(0)?.is_some() {}
+
+    if robots.is_empty() {
+        submenu.append(&MenuItem::new(app, "No robots", false, None::<&str>)?)?;
+        return Ok(());
+    }
+
+    for robot in robots {
+        let state = robot.status.as_deref().unwrap_or("unknown");
+        let entry = Submenu::with_items(
+            app,
+            format!("{} ({})", robot.label(), state),
+            true,
+            &[
+                &CheckMenuItem::with_id(
+                    app,
+                    format!("{}{}", ROBOT_TOGGLE_PREFIX, robot.id),
+                    "Running",
+                    true,
+                    robot.is_running(),
+                    None::<&str>,
+                )?,
+                &MenuItem::with_id(
+                    app,
+                    format!("{}{}", ROBOT_ACCESS_PREFIX, robot.id),
+                    "Open Access URL",
+                    true,
+                    None::<&str>,
+                )?,
+            ],
+        )?;
+        submenu.append(&entry)?;
+    }
+    Ok(())
+}
+
+/// Rebuilds the Previews submenu: one entry per running preview server, then
+/// an action stopping them all.
+pub fn set_previews(app: &AppHandle, previews: &[Preview]) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    if let Err(e) = fill_previews(app, &menu.previews, previews) {
+        logs::warn(
+            app,
+            &format!("Failed to update the tray preview list: {}", e),
+        );
+    }
+}
+
+fn fill_previews(
+    app: &AppHandle,
+    submenu: &Submenu<Wry>,
+    previews: &[Preview],
+) -> tauri::Result<()> {
+    while submenu.remove_at(0)?.is_some() {}
+
+    if previews.is_empty() {
+        submenu.append(&MenuItem::new(
+            app,
+            "No running previews",
+            false,
+            None::<&str>,
+        )?)?;
+        return Ok(());
+    }
+
+    for preview in previews {
+        let entry = Submenu::with_items(
+            app,
+            format!("{} (port {})", preview.task_id, preview.port),
+            true,
+            &[
+                &MenuItem::with_id(
+                    app,
+                    format!("{}{}", PREVIEW_OPEN_PREFIX, preview.task_id),
+                    "Open in Browser",
+                    true,
+                    None::<&str>,
+                )?,
+                &MenuItem::with_id(
+                    app,
+                    format!("{}{}", PREVIEW_STOP_PREFIX, preview.task_id),
+                    "Stop",
+                    true,
+                    None::<&str>,
+                )?,
+            ],
+        )?;
+        submenu.append(&entry)?;
+    }
+    submenu.append(&PredefinedMenuItem::separator(app)?)?;
+    submenu.append(&MenuItem::with_id(
+        app,
+        "preview_stop_all",
+        "Stop All Previews",
+        true,
+        None::<&str>,
+    )?)?;
+    Ok(())
+}
+
+/// Rebuilds the Profile submenu with a check mark on the active profile.
+pub fn set_profiles(app: &AppHandle) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    let list = app.state::<Profiles>().list();
+    if let Err(e) = fill_profiles(app, &menu.profiles, &list) {
+        logs::warn(
+            app,
+            &format!("Failed to update the tray profile list: {}", e),
+        );
+    }
+}
+
+fn fill_profiles(app: &AppHandle, submenu: &Submenu<Wry>, list: &ProfileList) -> tauri::Result<()> {
+    while submenu.remove_at(0)?.is_some() {}
+
+    for name in &list.profiles {
+        submenu.append(&CheckMenuItem::with_id(
+            app,
+            format!("{}{}", PROFILE_PREFIX, name),
+            name,
+            true,
+            *name == list.active,
+            None::<&str>,
+        )?)?;
+    }
+    Ok(())
+}
+
+/// Checks the entry of the close behavior in the settings. Clicking a check
+/// item toggles it, so this also undoes unchecking the current one.
+pub fn set_close_behavior(app: &AppHandle) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    let behavior = settings::current(app).close_behavior;
+    let _ = menu.close_tray.set_checked(behavior == CloseBehavior::Tray);
+    let _ = menu.close_quit.set_checked(behavior == CloseBehavior::Quit);
+    let _ = menu.close_ask.set_checked(behavior == CloseBehavior::Ask);
+}
+
+fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        _ => format!("{}h {}m", secs / 3600, secs / 60 % 60),
+    }
+}
+
+/// Plain-text report for bug reports: versions, backend state and recent logs.
+fn diagnostics(app: &AppHandle) -> String {
+    let supervisor = app.state::<Supervisor>();
+    let health = app.state::<HealthMonitor>().health();
+    let logger = app.state::<Logger>();
+
+    let mut report = format!(
+        "OpenAsst {} ({} {})\n\
+         Sidecar: {}\n\
+         Endpoint: {}\n\
+         Uptime: {}\n\
+         Health: {}\n\
+         API version: {}\n\
+         Logs: {}\n\
+         \n\
+         Recent log lines:\n",
+        app.package_info().version,
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        serde_json::to_string(&supervisor.state()).unwrap_or_default(),
+        supervisor.endpoint().describe(),
+        supervisor
+            .uptime()
+            .map(format_uptime)
+            .unwrap_or_else(|| "-".to_string()),
+        health.summary(),
+        health.version.as_deref().unwrap_or("unknown"),
+        logger.dir().display(),
+    );
+    for line in logger.tail(DIAGNOSTICS_LOG_LINES) {
+        report.push_str(&line);
+        report.push('\n');
+    }
+    report
+}
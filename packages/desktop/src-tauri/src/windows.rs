@@ -0,0 +1,55 @@
+use tauri::{AppHandle, Manager, WebviewWindow, WebviewWindowBuilder};
+
+use crate::cli::Cli;
+use crate::{logs, profiles, quick_prompt, settings};
+
+/// Label of the main window (see `app.windows` in tauri.conf.json).
+pub const MAIN_WINDOW_LABEL: &str = "main";
+
+/// Managed state recording how the shell was started.
+pub struct WindowMode {
+    headless: bool,
+}
+
+/// Creates the windows unless the app runs headless, in which case only the
+/// sidecar and tray come up and each window is created on first use.
+///
+/// Windows are declared with `create: false` in tauri.conf.json so that
+/// nothing is created before this decides.
+pub fn setup(app: &AppHandle) -> tauri::Result<()> {
+    let headless = app.state::<Cli>().headless || settings::current(app).headless;
+    app.manage(WindowMode { headless });
+    if headless {
+        logs::info(app, "Running headless; windows open from the tray");
+        return Ok(());
+    }
+    get_or_create(app, MAIN_WINDOW_LABEL)?;
+    get_or_create(app, quick_prompt::WINDOW_LABEL)?;
+    Ok(())
+}
+
+pub fn is_headless(app: &AppHandle) -> bool {
+    app.try_state::<WindowMode>()
+        .is_some_and(|mode| mode.headless)
+}
+
+/// Returns the window labelled `label`, creating it from its entry in
+/// tauri.conf.json if it does not exist yet.
+pub fn get_or_create(app: &AppHandle, label: &str) -> tauri::Result<WebviewWindow> {
+    if let Some(window) = app.get_webview_window(label) {
+        return Ok(window);
+    }
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|config| config.label == label)
+        .ok_or(tauri::Error::WindowNotFound)?
+        .clone();
+    let mut builder = WebviewWindowBuilder::from_config(app, &config)?;
+    if label == MAIN_WINDOW_LABEL {
+        builder = builder.title(profiles::window_title(app));
+    }
+    builder.build()
+}
@@ -1,4 +1,4 @@
-import { useState } from 'react';
+import { useState, useEffect } from 'react';
 import { ChatView } from './components/chat/ChatView';
 import { ArtifactPanel } from './components/artifacts/ArtifactPanel';
 import { Sidebar, ViewType } from './components/layout/Sidebar';
@@ -18,6 +18,12 @@ import { RegisterPage } from './components/auth/RegisterPage';
 import { AuthCallback } from './components/auth/AuthCallback';
 import { LandingPage } from './components/landing/LandingPage';
 import { useAuth } from './hooks/useAuth';
+import {
+  onDesktopEvent,
+  takePendingDeepLink,
+  DEEP_LINK_EVENT,
+  type DeepLink,
+} from './lib/desktop';
 
 type AuthView = 'landing' | 'login' | 'register' | 'callback';
 
@@ -25,6 +31,33 @@ export function App() {
   const { user, loading } = useAuth();
   const [activeView, setActiveView] = useState<ViewType>('chat');
   const [authView, setAuthView] = useState<AuthView>('landing');
+  const [focusDeviceId, setFocusDeviceId] = useState<string | null>(null);
+  const [focusSessionId, setFocusSessionId] = useState<string | null>(null);
+  const [focusScriptId, setFocusScriptId] = useState<string | null>(null);
+
+  // openasst:// links and devices picked from the tray. Each waits in the
+  // shell until taken here, so one sent while this page was loading is
+  // picked up once it listens.
+  useEffect(() => {
+    const open = (link: DeepLink) => {
+      switch (link.kind) {
+        case 'session':
+          setActiveView('chat');
+          setFocusSessionId(link.id);
+          break;
+        case 'device':
+          setActiveView('servers');
+          setFocusDeviceId(link.id);
+          break;
+        case 'marketplaceScript':
+          setActiveView('marketplace');
+          setFocusScriptId(link.id);
+          break;
+      }
+    };
+    const take = () => takePendingDeepLink().then((link) => link && open(link));
+    return onDesktopEvent<null>(DEEP_LINK_EVENT, take, take);
+  }, []);
 
   // Handle OAuth callback
   if (window.location.hash.includes('access_token')) {
@@ -65,7 +98,7 @@ export function App() {
         {activeView === 'chat' && (
           <>
             <div className="flex-1 flex flex-col">
-              <ChatView />
+              <ChatView focusSessionId={focusSessionId} />
             </div>
             <div className="w-[380px] shrink-0">
               <ArtifactPanel workDir="" taskId="" files={[]} />
@@ -74,7 +107,7 @@ export function App() {
         )}
 
         {activeView === 'servers' && (
-          <div className="flex-1"><ServerManagement /></div>
+          <div className="flex-1"><ServerManagement focusDeviceId={focusDeviceId} /></div>
         )}
         {activeView === 'documents' && (
           <div className="flex-1"><DocumentsView /></div>
@@ -98,7 +131,7 @@ export function App() {
           <div className="flex-1"><RobotStoreView /></div>
         )}
         {activeView === 'marketplace' && (
-          <div className="flex-1"><MarketplaceView /></div>
+          <div className="flex-1"><MarketplaceView focusScriptId={focusScriptId} /></div>
         )}
         {activeView === 'skills' && (
           <div className="flex-1"><SkillsView /></div>
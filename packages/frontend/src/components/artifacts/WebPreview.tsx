@@ -59,7 +59,13 @@ export function WebPreview({ taskId, workDir }: WebPreviewProps) {
           <Square size={12} />
         </button>
       </div>
-      <iframe src={url} className="flex-1 bg-white" />
+      {/* Sandboxed, and cross-origin to the app, so the previewed page cannot
+          reach the app or the API through it */}
+      <iframe
+        src={url}
+        className="flex-1 bg-white"
+        sandbox="allow-scripts allow-same-origin allow-forms"
+      />
     </div>
   );
 }
@@ -8,7 +8,12 @@ import { SessionSidebar } from './SessionSidebar';
 import { Bot, AlertTriangle, Copy, Check } from 'lucide-react';
 import { API_BASE_URL } from '../../lib/config';
 
-export function ChatView() {
+interface ChatViewProps {
+  /** Session to open, e.g. from an openasst:// link. */
+  focusSessionId?: string | null;
+}
+
+export function ChatView({ focusSessionId }: ChatViewProps) {
   const {
     messages, isRunning, phase, currentPlan, chatSessionId,
     sendMessage, stop, clear, approvePlan, rejectPlan, loadSession,
@@ -23,6 +28,12 @@ export function ChatView() {
     });
   }, [messages]);
 
+  useEffect(() => {
+    if (!focusSessionId) return;
+    sessionList.select(focusSessionId);
+    loadSession(focusSessionId);
+  }, [focusSessionId, loadSession, sessionList.select]);
+
   const handleNewChat = () => {
     clear();
     sessionList.select(null);
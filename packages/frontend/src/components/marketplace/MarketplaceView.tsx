@@ -7,9 +7,14 @@ import { CreateScriptModal } from './CreateScriptModal';
 
 const CATEGORIES = ['all', 'deployment', 'maintenance', 'monitoring', 'docker', 'custom'];
 
-export function MarketplaceView() {
+interface MarketplaceViewProps {
+  /** Script to show, e.g. from an openasst:// link. */
+  focusScriptId?: string | null;
+}
+
+export function MarketplaceView({ focusScriptId }: MarketplaceViewProps) {
   const {
-    scripts, loading, fetchScripts, searchScripts,
+    scripts, loading, fetchScripts, searchScripts, getScript,
     createScript, likeScript, favoriteScript, rateScript,
   } = useMarketplace();
 
@@ -22,6 +27,11 @@ export function MarketplaceView() {
     fetchScripts(category === 'all' ? undefined : category);
   }, [category, fetchScripts]);
 
+  useEffect(() => {
+    if (!focusScriptId) return;
+    getScript(focusScriptId).then((script) => script && setSelectedScript(script));
+  }, [focusScriptId, getScript]);
+
   const handleSearch = () => {
     if (searchQuery.trim()) {
       searchScripts(searchQuery);
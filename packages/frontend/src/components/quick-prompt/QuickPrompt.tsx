@@ -0,0 +1,95 @@
+import { useState, useEffect, useRef } from 'react';
+import { Bot, ExternalLink, X } from 'lucide-react';
+import { useChat } from '../../hooks/useChat';
+import { API_BASE_URL } from '../../lib/config';
+import { hideQuickPrompt, openSessionInMainWindow } from '../../lib/desktop';
+import { InputBar } from '../chat/InputBar';
+import { MessageBubble } from '../chat/MessageBubble';
+import { PlanView } from '../chat/PlanView';
+import type { Device } from '../server-mgmt/DeviceList';
+
+// Content of the frameless "quick ask" window summoned by the global shortcut.
+export function QuickPrompt() {
+  const [devices, setDevices] = useState<Device[]>([]);
+  const [deviceId, setDeviceId] = useState('');
+  const {
+    messages, isRunning, chatSessionId,
+    sendMessage, stop, clear, approvePlan, rejectPlan,
+  } = useChat({ deviceId: deviceId || undefined });
+  const scrollRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    fetch(`${API_BASE_URL}/devices`)
+      .then((res) => (res.ok ? res.json() : { devices: [] }))
+      .then((data) => setDevices(Array.isArray(data) ? data : data.devices || []))
+      .catch(() => {});
+  }, []);
+
+  useEffect(() => {
+    scrollRef.current?.scrollTo({ top: scrollRef.current.scrollHeight });
+  }, [messages]);
+
+  useEffect(() => {
+    const onKeyDown = (e: KeyboardEvent) => {
+      if (e.key === 'Escape') hideQuickPrompt();
+    };
+    window.addEventListener('keydown', onKeyDown);
+    return () => window.removeEventListener('keydown', onKeyDown);
+  }, []);
+
+  const handleOpenInMain = () => {
+    if (!chatSessionId) return;
+    openSessionInMainWindow(chatSessionId);
+    clear();
+  };
+
+  return (
+    <div className="h-screen flex flex-col bg-page text-ink rounded-xl border border-stone-300 overflow-hidden">
+      <div data-tauri-drag-region className="flex items-center gap-2 px-3 py-2 border-b border-stone-200">
+        <Bot size={16} className="text-accent" />
+        <span data-tauri-drag-region className="font-heading font-semibold text-sm flex-1">Quick Ask</span>
+        <select
+          value={deviceId}
+          onChange={(e) => {
+            setDeviceId(e.target.value);
+            clear();
+          }}
+          disabled={isRunning}
+          className="text-xs bg-surface border border-stone-300 rounded-lg px-2 py-1
+            focus:outline-none focus:border-accent"
+        >
+          <option value="">This computer</option>
+          {devices.map((d) => (
+            <option key={d.id} value={d.id}>{d.label}</option>
+          ))}
+        </select>
+        <button
+          onClick={handleOpenInMain}
+          disabled={!chatSessionId}
+          title="Open in main window"
+          className="p-1 text-ink-muted hover:text-accent disabled:opacity-40"
+        >
+          <ExternalLink size={14} />
+        </button>
+        <button onClick={() => hideQuickPrompt()} className="p-1 text-ink-muted hover:text-ink">
+          <X size={14} />
+        </button>
+      </div>
+
+      <div ref={scrollRef} className="flex-1 overflow-y-auto py-2">
+        {messages.map((msg) =>
+          msg.type === 'plan' && msg.plan ? (
+            <div key={msg.id} className="px-4 py-2">
+              <PlanView plan={msg.plan} onApprove={approvePlan} onReject={rejectPlan} />
+            </div>
+          ) : (
+            <MessageBubble key={msg.id} message={msg} />
+          ),
+        )}
+        {isRunning && <p className="px-4 py-2 text-xs text-ink-muted">Working...</p>}
+      </div>
+
+      <InputBar onSend={sendMessage} onStop={stop} isRunning={isRunning} />
+    </div>
+  );
+}
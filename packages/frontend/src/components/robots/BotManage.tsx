@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react';
 import { ArrowLeft, ExternalLink, RefreshCw, Play, Square, Copy, CheckCircle } from 'lucide-react';
 import type { DeployedBot } from './types';
+import { API_BASE_URL } from '../../lib/config';
 
 interface BotManageProps {
   bot: DeployedBot;
@@ -9,7 +10,7 @@ interface BotManageProps {
   onStop: () => void;
 }
 
-const API = 'http://127.0.0.1:2026';
+const API = API_BASE_URL;
 
 type Tab = 'overview' | 'channels' | 'config';
 
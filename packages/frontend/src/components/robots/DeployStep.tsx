@@ -1,5 +1,6 @@
 import { useState, useEffect, useRef } from 'react';
 import { CheckCircle, XCircle, Loader2, AlertTriangle, Info } from 'lucide-react';
+import { API_BASE_URL } from '../../lib/config';
 
 interface DeployLog {
   step: string;
@@ -15,7 +16,7 @@ interface DeployStepProps {
   onDone: (botId: string, success: boolean) => void;
 }
 
-const API = 'http://127.0.0.1:2026';
+const API = API_BASE_URL;
 
 export function DeployStep({ deviceId, name, config, onDone }: DeployStepProps) {
   const [logs, setLogs] = useState<DeployLog[]>([]);
@@ -173,7 +174,7 @@ async function startDeploy(
   try {
     const res = await fetch(`${API}/robots/deploy`, {
       method: 'POST', signal,
-      headers: { 'Content-Type': 'application/json' },
+      headers: { 'Content-Type': 'application/json', Accept: 'text/event-stream' },
       body: JSON.stringify({ deviceId, name, config }),
     });
     if (!res.ok || !res.body) {
@@ -1,6 +1,7 @@
 import { useState } from 'react';
 import { Plus, Trash2 } from 'lucide-react';
 import type { ModelProvider } from './types';
+import { API_BASE_URL } from '../../lib/config';
 
 interface ModelStepProps {
   providers: ModelProvider[];
@@ -110,7 +111,7 @@ function AddProviderForm({ onAdd, onCancel }: {
     setTesting(true); setTestError(''); setModels([]);
     const p = presets.find((x) => x.id === preset)!;
     try {
-      const res = await fetch('http://127.0.0.1:2026/robots/test-model', {
+      const res = await fetch(`${API_BASE_URL}/robots/test-model`, {
         method: 'POST',
         headers: { 'Content-Type': 'application/json' },
         body: JSON.stringify({ api: p.api, baseUrl, apiKey }),
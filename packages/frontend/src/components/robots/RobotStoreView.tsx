@@ -4,8 +4,9 @@ import type { DeployedBot } from './types';
 import { DeployWizard } from './DeployWizard';
 import { BotCard } from './BotCard';
 import { BotManage } from './BotManage';
+import { API_BASE_URL } from '../../lib/config';
 
-const API = 'http://127.0.0.1:2026/robots';
+const API = `${API_BASE_URL}/robots`;
 
 type PageView = 'list' | 'deploy' | 'manage';
 
@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback } from 'react';
 import { Server, CheckCircle, XCircle, Loader2, Wifi, WifiOff } from 'lucide-react';
+import { API_BASE_URL } from '../../lib/config';
 
 interface ServerInfo {
   id: string;
@@ -16,7 +17,7 @@ interface ServerStepProps {
   onBotNameChange: (name: string) => void;
 }
 
-const API = 'http://127.0.0.1:2026';
+const API = API_BASE_URL;
 
 export function ServerStep({ selectedId, onSelect, botName, onBotNameChange }: ServerStepProps) {
   const [servers, setServers] = useState<ServerInfo[]>([]);
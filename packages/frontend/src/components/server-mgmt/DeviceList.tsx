@@ -29,14 +29,15 @@ export interface Device {
 }
 
 interface DeviceListProps {
+  selectedId?: string | null;
   onSelectDevice: (device: Device) => void;
   onAddDevice: () => void;
 }
 
-export function DeviceList({ onSelectDevice, onAddDevice }: DeviceListProps) {
+export function DeviceList({ selectedId: initialSelectedId, onSelectDevice, onAddDevice }: DeviceListProps) {
   const [devices, setDevices] = useState<Device[]>([]);
   const [loading, setLoading] = useState(true);
-  const [selectedId, setSelectedId] = useState<string | null>(null);
+  const [selectedId, setSelectedId] = useState<string | null>(initialSelectedId ?? null);
   const [connectingId, setConnectingId] = useState<string | null>(null);
   const [connectResult, setConnectResult] = useState<{ id: string; ok: boolean; error?: string } | null>(null);
 
@@ -59,6 +60,10 @@ export function DeviceList({ onSelectDevice, onAddDevice }: DeviceListProps) {
     fetchDevices();
   }, []);
 
+  useEffect(() => {
+    if (initialSelectedId) setSelectedId(initialSelectedId);
+  }, [initialSelectedId]);
+
   const handleSelect = (device: Device) => {
     setSelectedId(device.id);
     onSelectDevice(device);
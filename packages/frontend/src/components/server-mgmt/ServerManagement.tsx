@@ -1,4 +1,4 @@
-import { useState, useCallback } from 'react';
+import { useState, useCallback, useEffect } from 'react';
 import { Monitor, Server } from 'lucide-react';
 import { API_BASE_URL } from '../../lib/config';
 import { DeviceList } from './DeviceList';
@@ -9,14 +9,26 @@ import type { Device } from './DeviceList';
 
 type ViewMode = 'idle' | 'workspace' | 'form';
 
-export function ServerManagement() {
+interface ServerManagementProps {
+  /** Device to open, e.g. when picked from the desktop tray. */
+  focusDeviceId?: string | null;
+}
+
+export function ServerManagement({ focusDeviceId }: ServerManagementProps) {
   const [viewMode, setViewMode] = useState<ViewMode>('idle');
-  const [selectedDevice, setSelectedDevice] = useState<Device | null>(null);
+  const [selectedDeviceId, setSelectedDeviceId] = useState<string | null>(focusDeviceId ?? null);
   const [editDevice, setEditDevice] = useState<Device | undefined>(undefined);
   const { hub } = useHub();
 
+  useEffect(() => {
+    if (focusDeviceId) {
+      setSelectedDeviceId(focusDeviceId);
+      setViewMode('workspace');
+    }
+  }, [focusDeviceId]);
+
   const handleSelectDevice = useCallback((device: Device) => {
-    setSelectedDevice(device);
+    setSelectedDeviceId(device.id);
     setEditDevice(undefined);
     setViewMode('workspace');
   }, []);
@@ -45,8 +57,8 @@ export function ServerManagement() {
   );
 
   const handleCancel = useCallback(() => {
-    setViewMode(selectedDevice ? 'workspace' : 'idle');
-  }, [selectedDevice]);
+    setViewMode(selectedDeviceId ? 'workspace' : 'idle');
+  }, [selectedDeviceId]);
 
   return (
     <div className="flex flex-col h-full bg-page text-ink">
@@ -69,6 +81,7 @@ export function ServerManagement() {
         {/* Left panel: Device list */}
         <div className="w-64 flex-shrink-0 border-r border-stone-200 overflow-hidden">
           <DeviceList
+            selectedId={selectedDeviceId}
             onSelectDevice={handleSelectDevice}
             onAddDevice={handleAddDevice}
           />
@@ -84,8 +97,8 @@ export function ServerManagement() {
             />
           )}
 
-          {viewMode === 'workspace' && selectedDevice && (
-            <DeviceWorkspace deviceId={selectedDevice.id} />
+          {viewMode === 'workspace' && selectedDeviceId && (
+            <DeviceWorkspace deviceId={selectedDeviceId} />
           )}
 
           {viewMode === 'idle' && (
@@ -105,7 +105,7 @@ export function useChat(options: UseChatOptions = {}) {
 
       const res = await fetch(`${API_BASE_URL}/agent`, {
         method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
+        headers: { 'Content-Type': 'application/json', Accept: 'text/event-stream' },
         body: JSON.stringify(body),
         signal: abortController.signal,
       });
@@ -230,7 +230,7 @@ export function useChat(options: UseChatOptions = {}) {
     try {
       const res = await fetch(`${API_BASE_URL}/agent/execute`, {
         method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
+        headers: { 'Content-Type': 'application/json', Accept: 'text/event-stream' },
         body: JSON.stringify({
           planId: currentPlan.id,
           prompt: currentPrompt,
@@ -36,7 +36,7 @@ export function useClusterExec() {
     try {
       const res = await fetch(`${API_BASE_URL}/hub/ai-execute`, {
         method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
+        headers: { 'Content-Type': 'application/json', Accept: 'text/event-stream' },
         body: JSON.stringify({ task, targetNames }),
         signal: abortController.signal,
       });
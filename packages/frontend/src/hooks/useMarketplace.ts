@@ -49,6 +49,16 @@ export function useMarketplace() {
     setLoading(false);
   }, []);
 
+  const getScript = useCallback(async (id: string): Promise<MarketScript | null> => {
+    try {
+      const res = await apiFetch(`/marketplace/scripts/${encodeURIComponent(id)}`);
+      if (!res.ok) return null;
+      return (await res.json()).script ?? null;
+    } catch {
+      return null;
+    }
+  }, []);
+
   const createScript = useCallback(async (scriptData: Partial<MarketScript>) => {
     const res = await apiFetch('/marketplace/scripts', {
       method: 'POST',
@@ -86,7 +96,7 @@ export function useMarketplace() {
 
   return {
     scripts, loading, error,
-    fetchScripts, searchScripts, createScript, deleteScript,
+    fetchScripts, searchScripts, getScript, createScript, deleteScript,
     likeScript, unlikeScript, favoriteScript, unfavoriteScript, rateScript,
   };
 }
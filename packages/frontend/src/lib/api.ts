@@ -1,7 +1,5 @@
 import { supabase } from './supabase';
-
-const API_PORT = 2026;
-const BASE = import.meta.env.VITE_API_URL || `http://127.0.0.1:${API_PORT}`;
+import { API_BASE_URL } from './config';
 
 export async function apiFetch(path: string, init?: RequestInit): Promise<Response> {
   const headers = new Headers(init?.headers);
@@ -21,5 +19,5 @@ export async function apiFetch(path: string, init?: RequestInit): Promise<Respon
     headers.set('X-User-Id', userId);
   }
 
-  return fetch(`${BASE}${path}`, { ...init, headers });
+  return fetch(`${API_BASE_URL}${path}`, { ...init, headers });
 }
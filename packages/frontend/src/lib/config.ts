@@ -1,2 +1,5 @@
 const API_PORT = 2026;
-export const API_BASE_URL = import.meta.env.VITE_API_URL ?? (import.meta.env.PROD ? '' : `http://127.0.0.1:${API_PORT}`);
+export const API_BASE_URL =
+  window.__OPENASST__?.apiBaseUrl ??
+  import.meta.env.VITE_API_URL ??
+  (import.meta.env.PROD ? '' : `http://127.0.0.1:${API_PORT}`);
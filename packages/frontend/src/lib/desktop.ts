@@ -0,0 +1,62 @@
+// Events sent by the desktop shell. Outside the desktop app these helpers do
+// nothing.
+
+export const DEEP_LINK_EVENT = 'deep-link://open';
+
+/** A validated `openasst://` link, as parsed by the shell. */
+export type DeepLink =
+  | { kind: 'session'; id: string }
+  | { kind: 'device'; id: string; view: 'terminal' }
+  | { kind: 'marketplaceScript'; id: string };
+
+/**
+ * The link waiting in the shell, if any. Returns it only once. The shell
+ * sends DEEP_LINK_EVENT whenever one arrives.
+ */
+export async function takePendingDeepLink(): Promise<DeepLink | null> {
+  const invoke = window.__TAURI__?.core.invoke;
+  if (!invoke) return null;
+  try {
+    return await invoke<DeepLink | null>('take_pending_deep_link');
+  } catch {
+    return null;
+  }
+}
+
+/** Hides the quick prompt window; no-op in other windows. */
+export function hideQuickPrompt(): void {
+  window.__TAURI__?.core.invoke('hide_quick_prompt').catch(() => {});
+}
+
+/** Continues a chat session from the quick prompt in the main window. */
+export function openSessionInMainWindow(sessionId: string): void {
+  window.__TAURI__?.core.invoke('open_session_in_main_window', { sessionId }).catch(() => {});
+}
+
+/**
+ * Subscribes to a shell event and returns a function that unsubscribes.
+ * `onListening` runs once events can no longer be missed.
+ */
+export function onDesktopEvent<T>(
+  event: string,
+  handler: (payload: T) => void,
+  onListening?: () => void,
+): () => void {
+  const listen = window.__TAURI__?.event.listen;
+  if (!listen) return () => {};
+
+  let unlisten: (() => void) | undefined;
+  let cancelled = false;
+  listen<T>(event, (e) => handler(e.payload)).then((fn) => {
+    if (cancelled) {
+      fn();
+    } else {
+      unlisten = fn;
+      onListening?.();
+    }
+  });
+  return () => {
+    cancelled = true;
+    unlisten?.();
+  };
+}
@@ -1,10 +1,12 @@
 import React from 'react';
 import ReactDOM from 'react-dom/client';
 import { App } from './App';
+import { QuickPrompt } from './components/quick-prompt/QuickPrompt';
 import './styles/globals.css';
 
 ReactDOM.createRoot(document.getElementById('root')!).render(
   <React.StrictMode>
-    <App />
+    {/* The desktop shell loads the quick prompt window at #quick-prompt */}
+    {window.location.hash === '#quick-prompt' ? <QuickPrompt /> : <App />}
   </React.StrictMode>,
 );
@@ -1 +1,17 @@
 /// <reference types="vite/client" />
+
+interface Window {
+  /** Injected by the desktop shell before the page loads. */
+  __OPENASST__?: {
+    apiBaseUrl: string;
+  };
+  /** Tauri's global API, present in the desktop app (`withGlobalTauri`). */
+  __TAURI__?: {
+    core: {
+      invoke<T>(command: string, args?: Record<string, unknown>): Promise<T>;
+    };
+    event: {
+      listen<T>(event: string, handler: (event: { payload: T }) => void): Promise<() => void>;
+    };
+  };
+}